@@ -0,0 +1,74 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use adventofcode_2016_rust::ioc::{line_from_file, Normalize};
+use adventofcode_2016_rust::{day1, day10, day2, day3, day4, day5, day6, day7, day8, day9};
+
+fn input(day: u8) -> String {
+    line_from_file(format!("./res/input-day{}.txt", day), &Normalize::default())
+        .unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Benchmarks parse, part 1 and part 2 of a day, for the real input and each of the examples.
+macro_rules! bench_day {
+    ($name:ident, $day:ident, $number:expr, $samples:expr, [$(($label:expr, $example:expr)),*]) => {
+        fn $name(c: &mut Criterion) {
+            let mut group = c.benchmark_group(stringify!($day));
+            group.sample_size($samples);
+
+            let inputs: Vec<(&str, String)> = vec![("input", input($number)) $(, ($label, $example.to_string()))*];
+            for (label, text) in &inputs {
+                group.bench_with_input(BenchmarkId::new("parse", label), text, |b, text| {
+                    b.iter(|| $day::parse(black_box(text)))
+                });
+
+                let parsed = $day::parse(text).unwrap();
+                group.bench_with_input(BenchmarkId::new("part1", label), &parsed, |b, parsed| {
+                    b.iter(|| $day::part1(black_box(parsed)))
+                });
+                group.bench_with_input(BenchmarkId::new("part2", label), &parsed, |b, parsed| {
+                    b.iter(|| $day::part2(black_box(parsed)))
+                });
+            }
+
+            group.finish();
+        }
+    };
+}
+
+bench_day!(bench_day1, day1, 1, 100, [
+    ("example", "R5, L5, R5, R3"),
+    ("example2", "R8, R4, R4, R8")
+]);
+bench_day!(bench_day2, day2, 2, 100, [
+    ("example", "ULL\nRRDDD\nLURDL\nUUUUD")
+]);
+bench_day!(bench_day3, day3, 3, 100, [
+    ("example", "101 301 501\n102 302 502\n103 303 503\n201 401 601\n202 402 602\n203 403 603")
+]);
+bench_day!(bench_day4, day4, 4, 100, [
+    ("example", "aaaaa-bbb-z-y-x-123[abxyz]\na-b-c-d-e-f-g-h-987[abcde]\nnot-a-real-room-404[oarel]\ntotally-real-room-200[decoy]")
+]);
+// Every iteration is a full MD5 search, so keep the number of samples to the minimum.
+bench_day!(bench_day5, day5, 5, 10, [
+    ("example", "abc")
+]);
+bench_day!(bench_day6, day6, 6, 100, [
+    ("example", "eedadn\ndrvtee\neandsr\nraavrd\natevrs\ntsrnev\nsdttsa\nrasrtv\nnssdts\nntnada\nsvetve\ntesnvt\nvntsnd\nvrdear\ndvrsen\nenarar")
+]);
+bench_day!(bench_day7, day7, 7, 100, [
+    ("example", "abba[mnop]qrst\nabcd[bddb]xyyx\naaaa[qwer]tyui\nioxxoj[asdfgh]zxcvbn"),
+    ("example2", "aba[bab]xyz\nxyx[xyx]xyx\naaa[kek]eke\nzazbz[bzb]cdb")
+]);
+bench_day!(bench_day8, day8, 8, 100, [
+    ("example", "rect 3x2\nrotate column x=1 by 1\nrotate row y=0 by 4\nrotate column x=1 by 1")
+]);
+bench_day!(bench_day9, day9, 9, 100, [
+    ("example", "X(8x2)(3x3)ABCY"),
+    ("example2", "(27x12)(20x12)(13x14)(7x10)(1x12)A"),
+    ("example3", "(25x3)(3x3)ABC(2x3)XY(5x2)PQRSTX(18x9)(3x2)TWO(5x7)SEVEN")
+]);
+// The day 10 example compares other chips than 17 and 61, so only the real input is used.
+bench_day!(bench_day10, day10, 10, 100, []);
+
+criterion_group!(benches, bench_day1, bench_day2, bench_day3, bench_day4, bench_day5,
+    bench_day6, bench_day7, bench_day8, bench_day9, bench_day10);
+criterion_main!(benches);
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use adventofcode_2016_rust::day1;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = day1::parse(input) {
+        let _ = day1::part1(&parsed);
+        let _ = day1::part2(&parsed);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use adventofcode_2016_rust::day10;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = day10::parse(input) {
+        let _ = day10::part1(&parsed);
+        let _ = day10::part2(&parsed);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use adventofcode_2016_rust::day2;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = day2::parse(input) {
+        let _ = day2::part1(&parsed);
+        let _ = day2::part2(&parsed);
+    }
+});
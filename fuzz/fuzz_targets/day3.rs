@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use adventofcode_2016_rust::day3;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = day3::parse(input) {
+        let _ = day3::part1(&parsed);
+        let _ = day3::part2(&parsed);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use adventofcode_2016_rust::day4;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = day4::parse(input) {
+        let _ = day4::part1(&parsed);
+        let _ = day4::part2(&parsed);
+    }
+});
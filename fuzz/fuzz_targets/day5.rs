@@ -0,0 +1,9 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use adventofcode_2016_rust::day5;
+
+// Solving searches millions of hashes for any door id, so only the parser is fuzzed.
+fuzz_target!(|input: &str| {
+    let _ = day5::parse(input);
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use adventofcode_2016_rust::day6;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = day6::parse(input) {
+        let _ = day6::part1(&parsed);
+        let _ = day6::part2(&parsed);
+    }
+});
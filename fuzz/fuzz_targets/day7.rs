@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use adventofcode_2016_rust::day7;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = day7::parse(input) {
+        let _ = day7::part1(&parsed);
+        let _ = day7::part2(&parsed);
+    }
+});
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use adventofcode_2016_rust::day8;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = day8::parse(input) {
+        let _ = day8::part1(&parsed);
+        let _ = day8::part2(&parsed);
+    }
+});
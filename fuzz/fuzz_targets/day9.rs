@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use adventofcode_2016_rust::day9;
+
+fuzz_target!(|input: &str| {
+    if let Ok(parsed) = day9::parse(input) {
+        let _ = day9::part1(&parsed);
+        let _ = day9::part2(&parsed);
+    }
+});
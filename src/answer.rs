@@ -0,0 +1,153 @@
+//! The answer to a part of a puzzle: a number, a string or a rendered screen.
+
+use std::convert::Infallible;
+use std::fmt;
+use std::str::FromStr;
+
+/// An answer as the puzzle asks for it.
+#[derive(Debug, Clone)]
+pub enum Answer {
+    /// A number.
+    Integer(i64),
+    /// A code or a name.
+    Text(String),
+    /// Rows of pixels, one line per row, to be read by eye.
+    Screen(String),
+}
+
+impl Answer {
+    /// The name of the variant: `integer`, `string` or `screen`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Answer::Integer(_) => "integer",
+            Answer::Text(_) => "string",
+            Answer::Screen(_) => "screen",
+        }
+    }
+
+    /// The answer as it would be typed in; screens keep their line breaks but not the last one.
+    fn text(&self) -> std::borrow::Cow<'_, str> {
+        match self {
+            Answer::Integer(i) => i.to_string().into(),
+            Answer::Text(s) => s.as_str().into(),
+            Answer::Screen(s) => s.trim_end_matches('\n').into(),
+        }
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Integer(i) => write!(f, "{}", i),
+            Answer::Text(s) | Answer::Screen(s) => f.write_str(s),
+        }
+    }
+}
+
+/// Answers are equal when they read the same, so a stored `"146"` matches the integer 146.
+impl PartialEq for Answer {
+    fn eq(&self, other: &Answer) -> bool {
+        self.text() == other.text()
+    }
+}
+
+impl Eq for Answer {}
+
+/// Compares by exact text, apart from the line break ending a screen.
+impl PartialEq<str> for Answer {
+    fn eq(&self, other: &str) -> bool {
+        match self {
+            Answer::Screen(_) => self.text() == other.trim_end_matches('\n'),
+            _ => self.text() == other,
+        }
+    }
+}
+
+impl PartialEq<&str> for Answer {
+    fn eq(&self, other: &&str) -> bool {
+        *self == **other
+    }
+}
+
+/// Reads stored text back as the kind of answer it looks like: several lines are a screen, a
+/// number written the way it prints an integer; anything else, like `01234567` or `+5`, stays text.
+impl FromStr for Answer {
+    type Err = Infallible;
+
+    fn from_str(text: &str) -> Result<Answer, Infallible> {
+        Ok(if text.contains('\n') {
+            Answer::Screen(text.to_string())
+        } else if let Some(i) = text.parse::<i64>().ok().filter(|i| i.to_string() == text) {
+            Answer::Integer(i)
+        } else {
+            Answer::Text(text.to_string())
+        })
+    }
+}
+
+/// Integers beyond the range of `i64` are kept as their digits.
+macro_rules! from_integer {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(value: $t) -> Answer {
+                i64::try_from(value).map_or_else(|_| Answer::Text(value.to_string()), Answer::Integer)
+            }
+        })*
+    };
+}
+
+from_integer!(u8, u16, u32, u64, usize, i32, i64);
+
+impl From<String> for Answer {
+    fn from(value: String) -> Answer {
+        Answer::Text(value)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(value: &str) -> Answer {
+        Answer::Text(value.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use crate::answer::Answer;
+
+    #[rstest]
+    #[case("146", "integer")]
+    #[case("C2C28", "string")]
+    #[case("01234567", "string")]
+    #[case("+5", "string")]
+    #[case("-5", "integer")]
+    #[case("#.\n.#\n", "screen")]
+    fn test_kind(#[case] answer: &str, #[case] expected: &str) {
+        assert_eq!(answer.parse::<Answer>().unwrap().kind(), expected);
+    }
+
+    #[rstest]
+    fn test_display() {
+        assert_eq!(Answer::from(146usize).to_string(), "146");
+        assert_eq!(Answer::from("C2C28").to_string(), "C2C28");
+        assert_eq!(Answer::Screen("#.\n.#\n".to_string()).to_string(), "#.\n.#\n");
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+        assert_eq!(Answer::from(i64::MIN).to_string(), "-9223372036854775808");
+    }
+
+    #[rstest]
+    #[case(Answer::from(146), "146", true)]
+    #[case(Answer::from(146), "147", false)]
+    #[case(Answer::from("01234567"), "01234567", true)]
+    #[case(Answer::from(1234567), "01234567", false)]
+    #[case(Answer::from(5), "+5", false)]
+    #[case(Answer::from(u64::MAX), "18446744073709551615", true)]
+    #[case(Answer::from(u64::MAX), "-1", false)]
+    #[case(Answer::from("61529"), "61529", true)]
+    #[case(Answer::from("abc"), "abc ", false)]
+    #[case(Answer::Screen("#.\n.#\n".to_string()), "#.\n.#", true)]
+    #[case(Answer::Screen("#.\n.#\n".to_string()), "#.\n##\n", false)]
+    fn test_eq(#[case] answer: Answer, #[case] expected: &str, #[case] equal: bool) {
+        assert_eq!(answer == expected, equal);
+    }
+}
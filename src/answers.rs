@@ -0,0 +1,109 @@
+//! Expected answers, used to verify a run.
+
+use std::collections::HashMap;
+use std::path::Path;
+use serde::Deserialize;
+use toml::Value;
+use crate::answer::Answer;
+use crate::config::day_key;
+use crate::error::{Error, Result};
+use crate::ioc::{line_from_file, Normalize};
+
+#[derive(Debug, Default, Deserialize)]
+struct Expected {
+    part1: Option<Value>,
+    part2: Option<Value>,
+}
+
+/// The expected answers per day, read from a file like
+///
+/// ```toml
+/// [day1]
+/// part1 = 146
+/// part2 = 131
+/// ```
+#[derive(Debug, Default)]
+pub struct Answers {
+    days: HashMap<u8, Expected>,
+}
+
+/// An expected answer as written in TOML: an integer, or a string that may span several lines.
+/// Quoted answers are kept as written, so `"01234567"` keeps its leading zero.
+pub fn to_answer(value: &Value) -> Result<Answer> {
+    match value {
+        Value::String(s) if s.contains('\n') => Ok(Answer::Screen(s.clone())),
+        Value::String(s) => Ok(Answer::Text(s.clone())),
+        Value::Integer(i) => Ok(Answer::Integer(*i)),
+        other => Err(Error::InvalidState(format!("expected answer must be a string or an integer, found {}", other.type_str()))),
+    }
+}
+
+impl Answers {
+    /// Reads the answers file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Answers> {
+        Answers::parse(&line_from_file(path, &Normalize::NONE)?)
+    }
+
+    /// Parses the `[dayN]` tables with their `part1` and `part2` answers.
+    pub fn parse(text: &str) -> Result<Answers> {
+        let table: HashMap<String, Expected> = toml::from_str(text).map_err(|e| Error::toml(text, &e))?;
+
+        let mut days = HashMap::new();
+        for (key, expected) in table {
+            let day = day_key(&key)?;
+            for value in [&expected.part1, &expected.part2].into_iter().flatten() {
+                to_answer(value)?;
+            }
+            days.insert(day, expected);
+        }
+
+        Ok(Answers { days })
+    }
+
+    /// The expected answer of the part, if there is one.
+    pub fn expected(&self, day: u8, part: u8) -> Option<Answer> {
+        let expected = self.days.get(&day)?;
+        let value = if part == 1 { expected.part1.as_ref() } else { expected.part2.as_ref() };
+        value.and_then(|value| to_answer(value).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use crate::answer::Answer;
+    use crate::answers::Answers;
+    use crate::error::Error;
+
+    #[rstest]
+    fn test_parse() {
+        let answers = Answers::parse("[day1]\npart1 = 146\npart2 = \"131\"\n\n[day8]\npart2 = '''\n#.\n.#\n'''\n").unwrap();
+        assert_eq!(answers.expected(1, 1), Some(Answer::Integer(146)));
+        assert_eq!(answers.expected(1, 2), Some(Answer::Integer(131)));
+        assert_eq!(answers.expected(8, 1), None);
+        assert_eq!(answers.expected(8, 2), Some(Answer::Screen("#.\n.#\n".to_string())));
+        assert_eq!(answers.expected(8, 2).unwrap().kind(), "screen");
+        assert_eq!(answers.expected(2, 1), None);
+    }
+
+    #[rstest]
+    fn test_parse_leading_zero() {
+        let answers = Answers::parse("[day5]\npart1 = \"01234567\"\n").unwrap();
+        assert_eq!(answers.expected(5, 1), Some(Answer::Text("01234567".to_string())));
+        assert_ne!(answers.expected(5, 1), Some(Answer::Integer(1234567)));
+    }
+
+    #[rstest]
+    #[case("[day1]\npart1 = ", 2)]
+    #[case("[day1]\npart1 = 1\n[day1]\n", 3)]
+    fn test_parse_error(#[case] input: &str, #[case] line: usize) {
+        assert!(matches!(Answers::parse(input), Err(Error::Parse { line: l, .. }) if l == line));
+    }
+
+    #[rstest]
+    #[case("[first]\npart1 = 1")]
+    #[case("[day1]\npart1 = 1.5")]
+    fn test_invalid(#[case] input: &str) {
+        assert!(matches!(Answers::parse(input), Err(Error::InvalidState(_))));
+    }
+}
@@ -0,0 +1,73 @@
+//! Cooperative cancellation: long running parts check a token, which is cancelled by hand or once
+//! its time budget is spent, and give up with what they found so far.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use crate::error::{Error, Result};
+
+/// A cancellation token; clones share whether it is cancelled.
+#[derive(Debug, Clone, Default)]
+pub struct Cancel {
+    cancelled: Arc<AtomicBool>,
+    deadline: Option<Instant>,
+}
+
+impl Cancel {
+    /// A token that is only cancelled by hand.
+    pub fn none() -> Cancel {
+        Cancel::default()
+    }
+
+    /// A token that is cancelled once `budget` has passed.
+    pub fn after(budget: Duration) -> Cancel {
+        Cancel { deadline: Instant::now().checked_add(budget), ..Cancel::default() }
+    }
+
+    /// Cancels this token and every clone of it.
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether the token was cancelled or its budget is spent.
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed) || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
+
+    /// Fails with [`Error::TimedOut`] and the partial answer once the token is cancelled.
+    pub fn check(&self, partial: impl FnOnce() -> String) -> Result<()> {
+        if self.is_cancelled() {
+            return Err(Error::TimedOut { partial: Some(partial()) });
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use rstest::rstest;
+    use crate::cancel::Cancel;
+    use crate::error::Error;
+
+    #[rstest]
+    fn test_none() {
+        assert!(!Cancel::none().is_cancelled());
+        assert!(!Cancel::after(Duration::from_secs(3600)).is_cancelled());
+        assert!(Cancel::none().check(|| unreachable!()).is_ok());
+    }
+
+    #[rstest]
+    fn test_cancel() {
+        let cancel = Cancel::none();
+        cancel.clone().cancel();
+        assert!(cancel.is_cancelled());
+        assert!(matches!(cancel.check(|| "4_3_".to_string()), Err(Error::TimedOut { partial: Some(p) }) if p == "4_3_"));
+    }
+
+    #[rstest]
+    fn test_after() {
+        assert!(Cancel::after(Duration::ZERO).is_cancelled());
+        assert!(!Cancel::after(Duration::MAX).is_cancelled());
+    }
+}
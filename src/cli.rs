@@ -0,0 +1,440 @@
+use std::num::NonZeroUsize;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+use clap::{Args, Parser, Subcommand};
+use toml::Value;
+use adventofcode_2016_rust::config::{self, Config};
+use adventofcode_2016_rust::examples;
+use adventofcode_2016_rust::ioc::InputSource;
+use adventofcode_2016_rust::output::Format;
+use adventofcode_2016_rust::profiles;
+use adventofcode_2016_rust::registry::{Day, DAYS};
+use adventofcode_2016_rust::report;
+use adventofcode_2016_rust::runner::{Job, RunOptions};
+
+#[derive(Debug, Parser)]
+#[command(name = "adventofcode-2016-rust", about = "Advent of Code 2016 solutions", args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    /// Log what the days do: `-v` for answers and failures, `-vv` for details like day 5 hashes,
+    /// `-vvv` for every step; RUST_LOG overrides this
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Write the log to this file instead of stderr
+    #[arg(long, value_name = "PATH", global = true)]
+    pub log_file: Option<PathBuf>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Run the selected days and print their answers (the default)
+    Run(RunArgs),
+    /// Run the selected days and compare their answers with the expected answers
+    Verify(VerifyArgs),
+    /// Run the selected days for every input profile and show their answers side by side
+    Compare(CompareArgs),
+    /// Run the selected days on the examples in res/examples and check their answers
+    Examples(ExamplesArgs),
+    /// Generate src/dayN.rs from the template, register it and create a placeholder input
+    NewDay(NewDayArgs),
+    /// Generate a random input for a day from a seed
+    Generate(GenerateArgs),
+    /// Run the selected days again whenever their input or examples change and show what changed
+    Watch(WatchArgs),
+    /// Run the selected days and write a Markdown or HTML report of their answers and timings
+    Report(ReportArgs),
+}
+
+#[derive(Debug, Clone, Args)]
+pub struct RunArgs {
+    /// Days to run, e.g. `3`, `1-5` or `all`
+    #[arg(value_name = "DAYS", default_value = "all")]
+    pub days: Vec<DaySelection>,
+
+    /// Run only this part of each selected day
+    #[arg(short, long, value_parser = clap::value_parser!(u8).range(1..=2))]
+    pub part: Option<u8>,
+
+    /// Input file as `[DAY=]PATH`; use `-` as PATH to read from stdin
+    #[arg(short, long = "input", value_name = "[DAY=]PATH")]
+    pub inputs: Vec<InputOverride>,
+
+    /// Read the inputs of this profile, res/<PROFILE>/input-dayN.txt, instead of the configured ones
+    #[arg(long, value_name = "PROFILE")]
+    pub profile: Option<String>,
+
+    /// Configuration with input files and parameters per day, defaults to ./aoc.toml if it exists
+    #[arg(short, long, value_name = "PATH")]
+    pub config: Option<PathBuf>,
+
+    /// Set a parameter of a day, e.g. `8.width=40` or `10.compare=[2,5]`, overriding the configuration
+    #[arg(long = "set", value_name = "DAY.KEY=VALUE")]
+    pub settings: Vec<Setting>,
+
+    /// Pass input to the puzzles exactly as read, without normalising newlines or blank lines
+    #[arg(long)]
+    pub raw: bool,
+
+    /// Measure parsing and each part over this many runs
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub runs: u32,
+
+    /// Unmeasured runs before the measured runs start
+    #[arg(long, default_value_t = 0)]
+    pub warmup: u32,
+
+    /// Time each part may take before it is stopped, e.g. `500ms`, `10s` or `2m`
+    #[arg(long, value_name = "DURATION", value_parser = parse_duration)]
+    pub budget: Option<Duration>,
+
+    /// Number of days to run at the same time, defaults to the number of CPUs
+    #[arg(short, long, value_parser = clap::value_parser!(u32).range(1..))]
+    pub jobs: Option<u32>,
+
+    /// Print a table with min/median/mean/stddev of every stage after the run
+    #[arg(long)]
+    pub timing_table: bool,
+
+    /// Output format: `text`, or one record per day and part as `json` lines or `csv`
+    #[arg(long, default_value = "text", value_name = "FORMAT")]
+    pub format: Format,
+}
+
+#[derive(Debug, Args)]
+pub struct VerifyArgs {
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    /// File with the expected answer of each day and part
+    #[arg(short, long, default_value = "./res/answers.toml")]
+    pub answers: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct CompareArgs {
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    /// Profiles to compare, defaults to `default` and every profile found in res/
+    #[arg(long, value_delimiter = ',', value_name = "PROFILES", conflicts_with_all = ["profile", "inputs"])]
+    pub profiles: Vec<String>,
+}
+
+#[derive(Debug, Args)]
+pub struct ExamplesArgs {
+    /// Days to check, e.g. `3`, `1-5` or `all`
+    #[arg(value_name = "DAYS", default_value = "all")]
+    pub days: Vec<DaySelection>,
+
+    /// Directory with an example directory per day, dayN/NAME.txt
+    #[arg(long, default_value = examples::DIR)]
+    pub dir: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct NewDayArgs {
+    /// Number of the day to generate
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+
+    /// Title of the puzzle, as listed in the registry
+    #[arg(short, long, default_value = "Untitled")]
+    pub title: String,
+
+    /// Root of the crate to add the day to
+    #[arg(long, default_value = ".")]
+    pub root: PathBuf,
+}
+
+#[derive(Debug, Args)]
+pub struct GenerateArgs {
+    /// Day to generate an input for
+    #[arg(value_parser = clap::value_parser!(u8).range(1..=25))]
+    pub day: u8,
+
+    /// Size of the input, counted in the unit of the day like instructions, rows or bots
+    #[arg(short, long)]
+    pub size: Option<usize>,
+
+    /// Seed of the random input, the same seed gives the same input
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+
+    /// File to write the input to instead of stdout
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct WatchArgs {
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    /// Directory with an example directory per day, dayN/NAME.txt
+    #[arg(long, default_value = examples::DIR)]
+    pub dir: PathBuf,
+
+    /// Milliseconds between two looks at the files
+    #[arg(long, default_value_t = 500, value_parser = clap::value_parser!(u64).range(1..))]
+    pub interval: u64,
+}
+
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    #[command(flatten)]
+    pub run: RunArgs,
+
+    /// File to write the report to instead of stdout
+    #[arg(short, long, value_name = "PATH")]
+    pub output: Option<PathBuf>,
+
+    /// `markdown` or `html`, defaults to html for a .html output file and markdown otherwise
+    #[arg(long, value_name = "FORMAT")]
+    pub report_format: Option<report::Format>,
+
+    /// Earlier report to compare the answers and timings with
+    #[arg(long, value_name = "PATH")]
+    pub previous: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DaySelection {
+    All,
+    Range(u8, u8),
+}
+
+impl DaySelection {
+    pub fn contains(&self, day: u8) -> bool {
+        match self {
+            DaySelection::All => true,
+            DaySelection::Range(from, to) => *from <= day && day <= *to,
+        }
+    }
+}
+
+impl FromStr for DaySelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s == "all" {
+            return Ok(DaySelection::All);
+        }
+
+        let parse = |text: &str| text.trim().parse::<u8>()
+            .map_err(|_| format!("invalid day '{}', expected a number, a range like 1-5 or 'all'", s));
+
+        match s.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (parse(from)?, parse(to)?);
+                if from > to {
+                    return Err(format!("invalid day range '{}', {} is after {}", s, from, to));
+                }
+                Ok(DaySelection::Range(from, to))
+            },
+            None => parse(s).map(|day| DaySelection::Range(day, day)),
+        }
+    }
+}
+
+/// A number of milliseconds, seconds or minutes, seconds when no unit is given.
+fn parse_duration(s: &str) -> Result<Duration, String> {
+    let (number, unit) = s.find(|ch: char| !ch.is_ascii_digit() && ch != '.').map_or((s, ""), |idx| s.split_at(idx));
+    let number: f64 = number.parse().map_err(|_| format!("invalid duration '{}', expected e.g. 500ms, 10s or 2m", s))?;
+    let seconds = match unit {
+        "ms" => number / 1000.0,
+        "" | "s" => number,
+        "m" => number * 60.0,
+        _ => return Err(format!("invalid unit '{}' in duration '{}', expected ms, s or m", unit, s)),
+    };
+    Duration::try_from_secs_f64(seconds).map_err(|e| format!("invalid duration '{}': {}", s, e))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct InputOverride {
+    pub day: Option<u8>,
+    pub source: InputSource,
+}
+
+impl FromStr for InputOverride {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (day, path) = match s.split_once('=') {
+            Some((day, path)) => {
+                let day = day.parse::<u8>().map_err(|_| format!("invalid day '{}' in input '{}'", day, s))?;
+                (Some(day), path)
+            },
+            None => (None, s),
+        };
+
+        let source = match path {
+            "" => return Err(format!("missing path in input '{}'", s)),
+            "-" => InputSource::Stdin,
+            path => InputSource::File(PathBuf::from(path)),
+        };
+
+        Ok(InputOverride { day, source })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Setting {
+    pub day: u8,
+    pub key: String,
+    pub value: Value,
+}
+
+impl FromStr for Setting {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid setting '{}', expected DAY.KEY=VALUE", s);
+        let (name, value) = s.split_once('=').ok_or_else(invalid)?;
+        let (day, key) = name.split_once('.').ok_or_else(invalid)?;
+        let day = day.strip_prefix("day").unwrap_or(day).parse::<u8>().map_err(|_| invalid())?;
+        if key.is_empty() {
+            return Err(invalid());
+        }
+
+        // Anything that is not a TOML value, like `abc`, is taken as a string.
+        let value = value.parse::<Value>().unwrap_or_else(|_| Value::String(value.to_string()));
+        Ok(Setting { day, key: key.to_string(), value })
+    }
+}
+
+impl RunArgs {
+    pub fn selects(&self, day: u8) -> bool {
+        self.days.iter().any(|selection| selection.contains(day))
+    }
+
+    pub fn options(&self) -> RunOptions {
+        let jobs = self.jobs.map_or_else(|| thread::available_parallelism().map_or(1, NonZeroUsize::get), |jobs| jobs as usize);
+        RunOptions { part: self.part, runs: self.runs, warmup: self.warmup, raw: self.raw, jobs, budget: self.budget }
+    }
+
+    /// The configuration given on the command line, or the default one when that exists.
+    pub fn config(&self) -> Result<Config, String> {
+        match &self.config {
+            Some(path) => Config::load(path),
+            None if Path::new(config::DEFAULT).exists() => Config::load(config::DEFAULT),
+            None => Ok(Config::default()),
+        }.map_err(|e| e.to_string())
+    }
+
+    /// The registered days selected on the command line, each with the source to read its input
+    /// from and its parameters, taken from the configuration unless overridden.
+    pub fn select_days(&self) -> Result<Vec<Job>, String> {
+        for selection in &self.days {
+            if let DaySelection::Range(from, to) = selection {
+                if let Some(day) = (*from..=*to).find(|n| !DAYS.iter().any(|d| d.number == *n)) {
+                    return Err(format!("day {} is not implemented", day));
+                }
+            }
+        }
+
+        let config = self.config()?;
+        let selected: Vec<&Day> = DAYS.iter().filter(|day| self.selects(day.number)).collect();
+        let mut sources: Vec<InputSource> = selected.iter()
+            .map(|day| InputSource::File(match self.profile.as_deref() {
+                Some(profile) if profile != profiles::DEFAULT => profiles::input(day, profile),
+                _ => config.input(day.number).map_or_else(|| PathBuf::from(day.input), PathBuf::from),
+            }))
+            .collect();
+        let mut params: Vec<_> = selected.iter().map(|day| config.params(day.number)).collect();
+
+        for setting in &self.settings {
+            let idx = selected.iter().position(|day| day.number == setting.day)
+                .ok_or(format!("parameter '{}' set for day {}, which is not selected", setting.key, setting.day))?;
+            params[idx].set(&setting.key, setting.value.clone());
+        }
+
+        for input in &self.inputs {
+            let idx = match input.day {
+                Some(number) => selected.iter().position(|day| day.number == number)
+                    .ok_or(format!("input '{}' given for day {}, which is not selected", input.source, number))?,
+                None if selected.len() == 1 => 0,
+                None => return Err(format!("input '{}' needs a day prefix (DAY=PATH) when running multiple days", input.source)),
+            };
+            sources[idx] = input.source.clone();
+        }
+
+        if sources.iter().filter(|source| **source == InputSource::Stdin).count() > 1 {
+            return Err("only one day can read its input from stdin".to_string());
+        }
+
+        Ok(selected.into_iter().zip(sources).zip(params)
+            .map(|((day, source), params)| Job { day, source, params })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+    use rstest::rstest;
+    use toml::Value;
+    use adventofcode_2016_rust::ioc::InputSource;
+    use crate::cli::{DaySelection, InputOverride, parse_duration, Setting};
+
+    #[rstest]
+    #[case("all", DaySelection::All)]
+    #[case("3", DaySelection::Range(3, 3))]
+    #[case("1-5", DaySelection::Range(1, 5))]
+    fn test_day_selection(#[case] input: &str, #[case] expected: DaySelection) {
+        assert_eq!(input.parse::<DaySelection>().unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("x")]
+    #[case("5-1")]
+    #[case("1-")]
+    fn test_day_selection_invalid(#[case] input: &str) {
+        assert!(input.parse::<DaySelection>().is_err());
+    }
+
+    #[rstest]
+    #[case("500ms", Some(Duration::from_millis(500)))]
+    #[case("10", Some(Duration::from_secs(10)))]
+    #[case("1.5s", Some(Duration::from_millis(1500)))]
+    #[case("2m", Some(Duration::from_secs(120)))]
+    #[case("2h", None)]
+    #[case("ms", None)]
+    fn test_parse_duration(#[case] input: &str, #[case] expected: Option<Duration>) {
+        assert_eq!(parse_duration(input).ok(), expected);
+    }
+
+    #[rstest]
+    #[case("input.txt", None, InputSource::File(PathBuf::from("input.txt")))]
+    #[case("3=res/day3.txt", Some(3), InputSource::File(PathBuf::from("res/day3.txt")))]
+    #[case("-", None, InputSource::Stdin)]
+    #[case("9=-", Some(9), InputSource::Stdin)]
+    fn test_input_override(#[case] input: &str, #[case] day: Option<u8>, #[case] source: InputSource) {
+        assert_eq!(input.parse::<InputOverride>().unwrap(), InputOverride { day, source });
+    }
+
+    #[rstest]
+    #[case("8.width=40", 8, "width", Value::Integer(40))]
+    #[case("day10.compare=[2, 5]", 10, "compare", Value::Array(vec![Value::Integer(2), Value::Integer(5)]))]
+    #[case("1.heading=east", 1, "heading", Value::String("east".to_string()))]
+    #[case("1.heading=\"NE\"", 1, "heading", Value::String("NE".to_string()))]
+    fn test_setting(#[case] input: &str, #[case] day: u8, #[case] key: &str, #[case] value: Value) {
+        assert_eq!(input.parse::<Setting>().unwrap(), Setting { day, key: key.to_string(), value });
+    }
+
+    #[rstest]
+    #[case("width=40")]
+    #[case("8.=40")]
+    #[case("x.width=40")]
+    fn test_setting_invalid(#[case] input: &str) {
+        assert!(input.parse::<Setting>().is_err());
+    }
+}
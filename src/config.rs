@@ -0,0 +1,145 @@
+//! Per-day input locations and puzzle parameters, read from a file like `aoc.toml`.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use toml::{Table, Value};
+use crate::error::{Error, Result};
+use crate::ioc::{line_from_file, Normalize};
+
+/// The file read when no configuration file is given, if it exists.
+pub const DEFAULT: &str = "./aoc.toml";
+
+/// The day number of a `[dayN]` table.
+pub fn day_key(key: &str) -> Result<u8> {
+    key.strip_prefix("day")
+        .and_then(|number| number.parse::<u8>().ok())
+        .ok_or_else(|| Error::InvalidState(format!("invalid table [{}], expected [dayN]", key)))
+}
+
+/// The parameters of a single day, every key of its table except `input`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Params(Table);
+
+impl Params {
+    /// Sets a parameter, replacing the value it had.
+    pub fn set(&mut self, key: &str, value: Value) {
+        self.0.insert(key.to_string(), value);
+    }
+
+    /// The parameters as the type a day expects; missing keys take the default of that type.
+    pub fn get<T: DeserializeOwned>(&self) -> Result<T> {
+        Value::Table(self.0.clone()).try_into()
+            .map_err(|e| Error::InvalidState(format!("invalid parameters: {}", e.message())))
+    }
+
+    /// Fails for a day without parameters that was given some anyway.
+    pub fn none(&self) -> Result<()> {
+        match self.0.keys().next() {
+            Some(key) => Err(Error::InvalidState(format!("unknown parameter '{}'", key))),
+            None => Ok(()),
+        }
+    }
+}
+
+impl From<Table> for Params {
+    fn from(table: Table) -> Params {
+        Params(table)
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct DayConfig {
+    input: Option<PathBuf>,
+    #[serde(flatten)]
+    params: Table,
+}
+
+/// The configuration of every day, from a file like
+///
+/// ```toml
+/// [day8]
+/// input = "./res/input-day8.txt"
+/// width = 50
+/// height = 6
+/// ```
+#[derive(Debug, Default)]
+pub struct Config {
+    days: HashMap<u8, DayConfig>,
+}
+
+impl Config {
+    /// Reads the configuration file at `path`.
+    pub fn load(path: impl AsRef<Path>) -> Result<Config> {
+        Config::parse(&line_from_file(path, &Normalize::NONE)?)
+    }
+
+    /// Parses the `[dayN]` tables of the configuration.
+    pub fn parse(text: &str) -> Result<Config> {
+        let table: HashMap<String, DayConfig> = toml::from_str(text).map_err(|e| Error::toml(text, &e))?;
+
+        let mut days = HashMap::new();
+        for (key, config) in table {
+            days.insert(day_key(&key)?, config);
+        }
+
+        Ok(Config { days })
+    }
+
+    /// The input file configured for the day, if any.
+    pub fn input(&self, day: u8) -> Option<&Path> {
+        self.days.get(&day)?.input.as_deref()
+    }
+
+    /// The parameters configured for the day, none when it has no table.
+    pub fn params(&self, day: u8) -> Params {
+        self.days.get(&day).map_or_else(Params::default, |config| Params(config.params.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use rstest::rstest;
+    use serde::Deserialize;
+    use toml::Value;
+    use crate::config::{Config, Params};
+    use crate::error::Error;
+
+    #[derive(Debug, Default, Deserialize, PartialEq)]
+    #[serde(default, deny_unknown_fields)]
+    struct Size {
+        width: usize,
+        height: usize,
+    }
+
+    #[rstest]
+    fn test_parse() {
+        let config = Config::parse("[day5]\ninput = 'door.txt'\n\n[day8]\nwidth = 7\nheight = 3\n").unwrap();
+        assert_eq!(config.input(5), Some(Path::new("door.txt")));
+        assert_eq!(config.input(8), None);
+        assert_eq!(config.params(5), Params::default());
+        assert_eq!(config.params(8).get::<Size>().unwrap(), Size { width: 7, height: 3 });
+        assert_eq!(config.params(1).get::<Size>().unwrap(), Size::default());
+    }
+
+    #[rstest]
+    #[case("[day8]\nwidth = ", 2)]
+    #[case("[day5]\ninput = 5\n", 2)]
+    fn test_parse_error(#[case] input: &str, #[case] line: usize) {
+        assert!(matches!(Config::parse(input), Err(Error::Parse { line: l, .. }) if l == line));
+    }
+
+    #[rstest]
+    fn test_invalid_params() {
+        let mut params = Config::parse("[day8]\nwidth = 'wide'\n").unwrap().params(8);
+        assert!(matches!(params.get::<Size>(), Err(Error::InvalidState(_))));
+        assert!(matches!(params.none(), Err(Error::InvalidState(_))));
+
+        params.set("width", Value::Integer(3));
+        params.set("depth", Value::Integer(3));
+        assert!(matches!(params.get::<Size>(), Err(Error::InvalidState(_))));
+        assert!(Params::default().none().is_ok());
+    }
+}
@@ -1,16 +1,58 @@
-pub fn part1(input: &Vec<String>) -> Result<i32,&str> {
-    Err("part 1 not yet implemented")
+//! Day N: template for a new day.
+
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+
+/// The input, one entry per line.
+pub fn parse(input: &str) -> Result<Vec<String>> {
+    Ok(input.lines().map(String::from).collect())
+}
+
+/// Part 1.
+pub fn part1(_input: &[String]) -> Result<i32> {
+    Err(Error::NotImplemented)
 }
 
-pub fn part2(input: &Vec<String>) -> Result<i32,&str> {
-    Err("part 2 not yet implemented")
+/// Part 2.
+pub fn part2(_input: &[String]) -> Result<i32> {
+    Err(Error::NotImplemented)
+}
+
+/// The [`Solution`] of day N.
+pub struct DayN;
+
+impl Solution for DayN {
+    type Input = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Vec<String>> {
+        parse(input)
+    }
+
+    fn part1(&self, input: &Vec<String>) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Vec<String>) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
+    use crate::dayN::{parse, part1, part2};
+
+    #[rstest]
+    #[case("", 0)]
+    #[ignore = "not yet implemented"]
+    fn test_part1(#[case] input: &str, #[case] expected: i32) {
+        assert_eq!(part1(&parse(input).unwrap()).unwrap(), expected);
+    }
 
     #[rstest]
-    fn test() {
+    #[case("", 0)]
+    #[ignore = "not yet implemented"]
+    fn test_part2(#[case] input: &str, #[case] expected: i32) {
+        assert_eq!(part2(&parse(input).unwrap()).unwrap(), expected);
     }
 }
@@ -1,147 +1,240 @@
-use std::collections::HashSet;
-use euclid::Point2D;
+//! Day 1: No Time for a Taxicab.
 
+use serde::Deserialize;
+use tracing::debug;
+use crate::config::Params;
+use crate::error::{Error, Result};
+use crate::geometry::{Heading, Point};
+use crate::solution::{Answer, Solution};
+
+/// The way to turn before walking.
 #[derive(Debug, Copy, Clone)]
-enum CommandDirection {
-    LEFT,
-    RIGHT,
+pub enum CommandDirection {
+    /// A quarter turn counter-clockwise.
+    Left,
+    /// A quarter turn clockwise.
+    Right,
 }
 
 impl CommandDirection {
-    fn from(cd: char) -> CommandDirection {
-        if cd == 'R' {
-            CommandDirection::RIGHT
-        } else {
-            CommandDirection::LEFT
+    fn from(cd: char) -> Result<CommandDirection> {
+        match cd {
+            'R' => Ok(CommandDirection::Right),
+            'L' => Ok(CommandDirection::Left),
+            _ => Err(Error::parse(1, format!("invalid direction '{}', expected 'L' or 'R'", cd))),
         }
     }
 }
 
+/// A single instruction like `R2`: turn, then walk `distance` blocks.
 #[derive(Debug, Copy, Clone)]
-struct Command {
-    direction: CommandDirection,
-    distance: i32,
+pub struct Command {
+    /// The way to turn.
+    pub direction: CommandDirection,
+    /// The number of blocks to walk.
+    pub distance: i32,
 }
 
 impl Command {
-    fn from(text: &str) -> Command {
-        let cd = text.chars().next().unwrap();
-        let distance = text[1..].parse().unwrap();
+    fn from(text: &str) -> Result<Command> {
+        let cd = text.chars().next()
+            .ok_or_else(|| Error::parse(1, "empty command"))?;
+        let distance = text[cd.len_utf8()..].parse::<u32>().ok()
+            .and_then(|distance| i32::try_from(distance).ok())
+            .ok_or_else(|| Error::parse(2, format!("invalid distance '{}'", &text[cd.len_utf8()..])))?;
 
-        Command {
-            direction: CommandDirection::from(cd),
+        Ok(Command {
+            direction: CommandDirection::from(cd)?,
             distance,
-        }
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-enum LocationDirection {
-    ZERO,
-    NORTH,
-    EAST,
-    SOUTH,
-    WEST,
-}
-
-impl LocationDirection {
-    fn move_to(&self, command_direction: &CommandDirection) -> LocationDirection {
-        match (self, command_direction) {
-            (LocationDirection::ZERO, CommandDirection::LEFT) => LocationDirection::WEST,
-            (LocationDirection::ZERO, CommandDirection::RIGHT) => LocationDirection::EAST,
-            (LocationDirection::NORTH, CommandDirection::LEFT) => LocationDirection::WEST,
-            (LocationDirection::NORTH, CommandDirection::RIGHT) => LocationDirection::EAST,
-            (LocationDirection::EAST, CommandDirection::LEFT) => LocationDirection::NORTH,
-            (LocationDirection::EAST, CommandDirection::RIGHT) => LocationDirection::SOUTH,
-            (LocationDirection::SOUTH, CommandDirection::LEFT) => LocationDirection::EAST,
-            (LocationDirection::SOUTH, CommandDirection::RIGHT) => LocationDirection::WEST,
-            (LocationDirection::WEST, CommandDirection::LEFT) => LocationDirection::SOUTH,
-            (LocationDirection::WEST, CommandDirection::RIGHT) => LocationDirection::NORTH,
-        }
+        })
     }
 }
 
+/// Position and facing direction while walking the city grid.
 #[derive(Debug, Copy, Clone)]
-struct Location {
-    direction: LocationDirection,
-    point: Point2D<i32, i32>,
+pub struct Location {
+    /// The way the walk is facing.
+    pub direction: Heading,
+    /// Where the walk is, relative to the start.
+    pub point: Point<i32>,
 }
 
 impl Location {
-    fn origin() -> Location {
+    /// The starting point, facing `direction`.
+    pub fn facing(direction: Heading) -> Location {
         Location {
-            direction: LocationDirection::ZERO,
-            point: Point2D::origin(),
+            direction,
+            point: Point::origin(),
         }
     }
 
-    fn move_to(&self, command: &Command) -> Vec<Location> {
-        let mut list: Vec<Location> = Vec::new();
-        let new_direction = self.direction.move_to(&command.direction);
-
-        let mut distance = command.distance;
-        let mut x = self.point.x;
-        let mut y = self.point.y;
-
-        while distance > 0 {
-            distance -= 1;
-            x = match new_direction {
-                LocationDirection::WEST => x - 1,
-                LocationDirection::EAST => x + 1,
-                _ => x,
-            };
-            y = match new_direction {
-                LocationDirection::NORTH => y + 1,
-                LocationDirection::SOUTH => y - 1,
-                _ => y,
-            };
-
-            list.push(Location {
-                direction: new_direction,
-                point: Point2D::new(x, y),
-            });
-        }
+    /// The location after turning and walking the whole distance of the command.
+    pub fn move_to(&self, command: &Command) -> Result<Location> {
+        let direction = match command.direction {
+            CommandDirection::Left => self.direction.left(),
+            CommandDirection::Right => self.direction.right(),
+        };
+        let step = direction.unit();
 
-        list
+        let walk = |from: i32, step: i32| step.checked_mul(command.distance).and_then(|blocks| from.checked_add(blocks));
+        match (walk(self.point.x, step.x), walk(self.point.y, step.y)) {
+            (Some(x), Some(y)) => Ok(Location { direction, point: Point::new(x, y) }),
+            _ => Err(Error::InvalidState(format!("walking {} blocks from {:?} leaves the map", command.distance, self.point))),
+        }
     }
+}
 
-    fn manhattan_distance(&self) -> i32 {
-        self.point.x.abs() + self.point.y.abs()
+fn distance(point: Point<i32>) -> Result<i32> {
+    point.manhattan_distance()
+        .ok_or_else(|| Error::InvalidState(format!("{:?} is too far away to tell", point)))
+}
+
+/// A straight part of the walk: `length` blocks in the direction of `step`, including `start`.
+#[derive(Debug, Copy, Clone)]
+struct Segment {
+    start: Point<i32>,
+    step: Point<i32>,
+    length: i32,
+}
+
+fn cross(a: (i64, i64), b: (i64, i64)) -> i64 {
+    a.0 * b.1 - a.1 * b.0
+}
+
+impl Segment {
+    /// The fewest blocks, at least one, walked along `self` to reach a point of `other`.
+    fn first_meeting(&self, other: &Segment) -> Option<i64> {
+        let d = (i64::from(self.step.x), i64::from(self.step.y));
+        let e = (i64::from(other.step.x), i64::from(other.step.y));
+        let w = (i64::from(other.start.x) - i64::from(self.start.x), i64::from(other.start.y) - i64::from(self.start.y));
+        let (n, m) = (i64::from(self.length), i64::from(other.length));
+
+        let det = cross(d, e);
+        let (from, to) = if det == 0 {
+            // Parallel, and with steps of the same size: only overlapping when on the same line.
+            if cross(w, d) != 0 {
+                return None;
+            }
+            let dot = |a: (i64, i64), b: (i64, i64)| a.0 * b.0 + a.1 * b.1;
+            let at = dot(w, d) / dot(d, d);
+            let along = dot(e, d) / dot(d, d) * m;
+            (at.min(at + along), at.max(at + along))
+        } else {
+            let (t, u) = (cross(w, e), cross(w, d));
+            if t % det != 0 || u % det != 0 || !(0..=m).contains(&(u / det)) {
+                return None;
+            }
+            (t / det, t / det)
+        };
+
+        let first = from.max(1);
+        (first <= to.min(n)).then_some(first)
     }
 }
 
-pub fn part1(input: &str) -> Result<i32, &str> {
-    let mut location = Location::origin();
+/// Parses the comma separated list of commands.
+pub fn parse(input: &str) -> Result<Vec<Command>> {
+    let mut commands = Vec::new();
+    let mut column = 1;
 
     for part in input.split(", ") {
-        location = *(location.move_to(&Command::from(part)).last().unwrap());
+        commands.push(Command::from(part).map_err(|e| e.at(1, column))?);
+        column += part.chars().count() + 2;
     }
 
-    Ok(location.manhattan_distance())
+    Ok(commands)
 }
 
-pub fn part2(input: &str) -> Result<i32, &str> {
-    let mut set: HashSet<Point2D<i32, i32>> = HashSet::new();
-    let mut location = Location::origin();
+/// Distance in blocks to the location at the end of the walk.
+pub fn part1(input: &[Command]) -> Result<i32> {
+    end_distance(Heading::North, input)
+}
 
-    set.insert(location.point);
-    for part in input.split(", ") {
-        let list = location.move_to(&Command::from(part));
-        for loc in list {
-            if !set.insert(loc.point) {
-                return Ok(loc.manhattan_distance());
-            }
-            location = loc;
+/// Distance in blocks to the first location visited twice.
+pub fn part2(input: &[Command]) -> Result<i32> {
+    revisit_distance(Heading::North, input)
+}
+
+/// Distance to the end of the walk, starting out facing `start`.
+pub fn end_distance(start: Heading, input: &[Command]) -> Result<i32> {
+    let mut location = Location::facing(start);
+
+    for command in input {
+        location = location.move_to(command)?;
+    }
+
+    distance(location.point)
+}
+
+/// Distance to the first location visited twice, starting out facing `start`.
+pub fn revisit_distance(start: Heading, input: &[Command]) -> Result<i32> {
+    let mut location = Location::facing(start);
+    let mut walked = vec![Segment { start: location.point, step: start.unit(), length: 0 }];
+
+    for command in input {
+        let next = location.move_to(command)?;
+        let segment = Segment { start: location.point, step: next.direction.unit(), length: command.distance };
+
+        if let Some(blocks) = walked.iter().filter_map(|other| segment.first_meeting(other)).min() {
+            // The walk up to `next` fitted on the map, so every point before it does too.
+            let blocks = blocks as i32;
+            let point = Point::new(location.point.x + segment.step.x * blocks, location.point.y + segment.step.y * blocks);
+            debug!(x = point.x, y = point.y, "visited twice");
+            return distance(point);
         }
+
+        walked.push(segment);
+        location = next;
+    }
+
+    Err(Error::NotFound("no location is visited twice".to_string()))
+}
+
+/// The way the walk starts out, configured as `heading`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Start {
+    /// The heading before the first turn.
+    pub heading: Heading,
+}
+
+impl Default for Start {
+    fn default() -> Self {
+        Start { heading: Heading::North }
+    }
+}
+
+/// The [`Solution`] of day 1.
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = (Start, Vec<Command>);
+
+    fn parse(&self, input: &str) -> Result<(Start, Vec<Command>)> {
+        Ok((Start::default(), parse(input)?))
     }
 
-    Err("No doubles")
+    fn parse_with(&self, input: &str, params: &Params) -> Result<(Start, Vec<Command>)> {
+        Ok((params.get()?, parse(input)?))
+    }
+
+    fn part1(&self, (start, input): &(Start, Vec<Command>)) -> Result<Answer> {
+        end_distance(start.heading, input).map(Answer::from)
+    }
+
+    fn part2(&self, (start, input): &(Start, Vec<Command>)) -> Result<Answer> {
+        revisit_distance(start.heading, input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashSet;
+    use proptest::prelude::*;
     use rstest::rstest;
-    use crate::day1::{part1, part2};
+    use crate::day1::{end_distance, parse, part1, part2, revisit_distance};
+    use crate::error::Error;
+    use crate::geometry::Heading;
 
     #[rstest]
     #[case("R2, L3", 5)]
@@ -149,12 +242,88 @@ mod tests {
     #[case("R2, R2, R2", 2)]
     #[case("R5, L5, R5, R3", 12)]
     fn test_part1(#[case] input: String, #[case] expected: i32) {
-        assert_eq!(expected, part1(&input).unwrap())
+        assert_eq!(expected, part1(&parse(&input).unwrap()).unwrap())
     }
 
     #[rstest]
     #[case("R8, R4, R4, R8", 4)]
     fn test_part2(#[case] input: String, #[case] expected: i32) {
-        assert_eq!(expected, part2(&input).unwrap())
+        assert_eq!(expected, part2(&parse(&input).unwrap()).unwrap())
+    }
+
+    #[rstest]
+    #[case(Heading::East, "R2, L3", 5)]
+    #[case(Heading::South, "R5, L5, R5, R3", 12)]
+    #[case(Heading::NorthEast, "R2, L3", 6)]
+    fn test_start(#[case] start: Heading, #[case] input: &str, #[case] expected: i32) {
+        assert_eq!(expected, end_distance(start, &parse(input).unwrap()).unwrap());
+        if start.is_orthogonal() {
+            assert_eq!(4, revisit_distance(start, &parse("R8, R4, R4, R8").unwrap()).unwrap());
+        }
+    }
+
+    #[rstest]
+    #[case("", 1, 1)]
+    #[case("R2, X3", 1, 5)]
+    #[case("R2, L3, Rx", 1, 10)]
+    #[case("R2, L-3", 1, 6)]
+    #[case("R2, é3", 1, 5)]
+    #[case("R2, Lé, R1", 1, 6)]
+    fn test_parse_error(#[case] input: &str, #[case] line: usize, #[case] column: usize) {
+        assert!(matches!(parse(input), Err(Error::Parse { line: l, column: c, .. }) if l == line && c == column));
+    }
+
+    #[rstest]
+    #[case("R2147483647, R2147483647, R2147483647, R2147483647", 0, Some(0))]
+    #[case("R1, L2, R3, L4, R5, L6", 21, None)]
+    #[case("R5, R2, R2, R5", 6, Some(3))]
+    #[case("R4, R0, R2", 2, Some(3))]
+    fn test_walks(#[case] input: &str, #[case] end: i32, #[case] revisit: Option<i32>) {
+        let commands = parse(input).unwrap();
+        assert_eq!(end, part1(&commands).unwrap());
+        assert_eq!(revisit, part2(&commands).ok());
+    }
+
+    #[rstest]
+    #[case("R2147483647, L0, R1")]
+    #[case("R2147483647, R1")]
+    fn test_off_the_map(#[case] input: &str) {
+        assert!(matches!(part1(&parse(input).unwrap()), Err(Error::InvalidState(_))));
+    }
+
+    /// A walk as the puzzle describes it: one block at a time, turning a quarter before each command.
+    fn walk(commands: &[(char, i32)]) -> (i32, Option<i32>) {
+        let (mut x, mut y, mut heading) = (0i32, 0i32, 0usize);
+        let mut visited = HashSet::from([(0, 0)]);
+        let mut revisit = None;
+
+        for (turn, distance) in commands {
+            heading = if *turn == 'R' { (heading + 1) % 4 } else { (heading + 3) % 4 };
+            for _ in 0..*distance {
+                match heading {
+                    0 => y -= 1,
+                    1 => x += 1,
+                    2 => y += 1,
+                    _ => x -= 1,
+                }
+                if !visited.insert((x, y)) && revisit.is_none() {
+                    revisit = Some(x.abs() + y.abs());
+                }
+            }
+        }
+
+        (x.abs() + y.abs(), revisit)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_matches_block_by_block_walk(commands in prop::collection::vec((prop::sample::select(vec!['L', 'R']), 0..50i32), 1..40)) {
+            let input = commands.iter().map(|(turn, distance)| format!("{}{}", turn, distance)).collect::<Vec<String>>().join(", ");
+            let parsed = parse(&input).unwrap();
+            let (end, revisit) = walk(&commands);
+
+            prop_assert_eq!(part1(&parsed).unwrap(), end);
+            prop_assert_eq!(part2(&parsed).ok(), revisit);
+        }
     }
 }
@@ -1,45 +1,61 @@
+//! Day 10: Balance Bots.
+
 use std::collections::HashMap;
-use lazy_static::lazy_static;
-use regex::Regex;
+use serde::Deserialize;
+use tracing::trace;
+use crate::cancel::Cancel;
+use crate::config::Params;
+use crate::error::{Error, Result};
+use crate::parser::{alt, literal, map, number, pair, parse_all, Parser, preceded};
+use crate::solution::{Answer, Solution};
 
+/// Where a bot hands its chips to.
 #[derive(Debug,Clone)]
-enum TargetId {
+pub enum TargetId {
+    /// The bot with this id.
     Bot(u8),
+    /// The output bin with this id.
     Output(u8),
 }
 
+/// A bot holding up to two chips, which it keeps after handing them on.
 #[derive(Debug,Clone)]
-struct Bot {
-    value_1: Option<u8>,
-    value_2: Option<u8>,
-    target_id_low: TargetId,
-    target_id_high: TargetId,
-    test_low : u8,
-    test_high : u8,
-    processed : bool,
+pub struct Bot {
+    /// The first chip, the lower one once the bot holds two.
+    pub value_1: Option<u8>,
+    /// The second chip, the higher one.
+    pub value_2: Option<u8>,
+    /// Where the lower chip goes.
+    pub target_id_low: TargetId,
+    /// Where the higher chip goes.
+    pub target_id_high: TargetId,
 }
 
 impl Bot {
-    fn new(target_id_low : TargetId, target_id_high : TargetId, test_low : u8, test_high : u8) -> Bot {
+    /// A bot without chips, handing them to the given targets.
+    pub fn new(target_id_low : TargetId, target_id_high : TargetId) -> Bot {
         Bot {
             value_1 : None,
             value_2 : None,
             target_id_low,
             target_id_high,
-            test_low,
-            test_high,
-            processed : false
         }
     }
 
-    fn accept(&mut self, value : u8) {
+    /// Whether the bot got, and so compared, the `low` and `high` chips.
+    pub fn compares(&self, low: u8, high: u8) -> bool {
+        self.value_1 == Some(low) && self.value_2 == Some(high)
+    }
+
+    /// Takes a chip, failing when the bot already holds two.
+    pub fn accept(&mut self, value : u8) -> Result<()> {
         // store the value as value_1 or value_2
         if self.value_1.is_none() {
             self.value_1 = Some(value);
         } else if self.value_2.is_none() {
             self.value_2 = Some(value);
         } else {
-            panic!("Cannot accept value {} on bot {:?}", value, self);
+            return Err(Error::InvalidState(format!("cannot accept value {} on bot {:?}", value, self)));
         }
 
         // Place value_1 and value_2 in the right order (low, high)
@@ -52,91 +68,109 @@ impl Bot {
 
             self.value_1 = Some(value_low);
             self.value_2 = Some(value_high);
-
-            // Update to record if right values have been processed
-            self.processed = self.test_low == value_low && self.test_high == value_high;
         }
+
+        Ok(())
     }
 }
 
-fn factory(input: &Vec<String>, test_low : u8, test_high : u8) -> (Vec<(u8,u8)>, HashMap<u8,Bot>,) {
-    lazy_static! {
-            static ref VALUE: Regex = Regex::new(r"value (\d+) goes to bot (\d+)").unwrap();
-            static ref MOVE: Regex = Regex::new(r"bot (\d+) gives low to (bot (\d+)|output (\d+)) and high to (bot (\d+)|output (\d+))").unwrap();
-        }
+/// A line of the instructions.
+enum Instruction {
+    Value { value: u8, bot_id: u8 },
+    Bot { id: u8, low: TargetId, high: TargetId },
+}
+
+fn target() -> impl Parser<TargetId> {
+    alt((
+        map(preceded(literal("bot "), number()), TargetId::Bot),
+        map(preceded(literal("output "), number()), TargetId::Output),
+    ))
+}
 
+fn instruction() -> impl Parser<Instruction> {
+    alt((
+        map(pair(preceded(literal("value "), number()), preceded(literal(" goes to bot "), number())),
+            |(value, bot_id)| Instruction::Value { value, bot_id }),
+        map(pair(preceded(literal("bot "), number()),
+                 pair(preceded(literal(" gives low to "), target()), preceded(literal(" and high to "), target()))),
+            |(id, (low, high))| Instruction::Bot { id, low, high }),
+    ))
+}
+
+/// The initial `(value, bot)` assignments and the bots by id.
+pub type Factory = (Vec<(u8,u8)>, HashMap<u8,Bot>);
+
+/// The instructions, one per line, as the factory they build.
+pub fn parse(input: &str) -> Result<Factory> {
     let mut bots : HashMap<u8,Bot> = HashMap::new();
     let mut values : Vec<(u8,u8)> = Vec::new();
 
-    for line in input {
-        if line.starts_with("value") {
-            if let Some(caps) = VALUE.captures(line) {
-                let value = caps.get(1).unwrap().as_str().parse::<u8>().unwrap();
-                let bot_id = caps.get(2).unwrap().as_str().parse::<u8>().unwrap();
-                values.push((value, bot_id));
-            } else {
-                panic!("Unable to parse 'value' line '{}'", line);
-            }
-        } else if line.starts_with("bot") {
-            if let Some(caps) = MOVE.captures(line) {
-                let id = caps.get(1).unwrap().as_str().parse::<u8>().unwrap();
-                let target_id_low = if caps.get(2).unwrap().as_str().starts_with("bot") {
-                    TargetId::Bot(caps.get(3).unwrap().as_str().parse::<u8>().unwrap())
-                } else {
-                    TargetId::Output(caps.get(4).unwrap().as_str().parse::<u8>().unwrap())
-                };
-                let target_id_high = if caps.get(5).unwrap().as_str().starts_with("bot") {
-                    TargetId::Bot(caps.get(6).unwrap().as_str().parse::<u8>().unwrap())
-                } else {
-                    TargetId::Output(caps.get(7).unwrap().as_str().parse::<u8>().unwrap())
-                };
-                bots.insert(id, Bot::new(target_id_low, target_id_high, test_low, test_high));
-            } else {
-                panic!("Unable to parse 'move' line '{}'", line);
-            }
-        } else {
-            panic!("Unable to parse line '{}'", line);
+    let instruction = instruction();
+    for (idx, line) in input.lines().enumerate() {
+        match parse_all(&instruction, line).map_err(|e| e.at(idx + 1, 1))? {
+            Instruction::Value { value, bot_id } => values.push((value, bot_id)),
+            Instruction::Bot { id, low, high } => {
+                if bots.insert(id, Bot::new(low, high)).is_some() {
+                    return Err(Error::parse(1, format!("bot {} is given instructions twice", id)).at(idx + 1, 1));
+                }
+            },
         }
     }
 
-    (values, bots)
+    Ok((values, bots))
 }
 
+fn bot(bots: &mut HashMap<u8, Bot>, bot_id: u8) -> Result<&mut Bot> {
+    bots.get_mut(&bot_id).ok_or_else(|| Error::NotFound(format!("bot {}", bot_id)))
+}
 
-fn process(values: &Vec<(u8, u8)>, bots : &mut HashMap<u8, Bot>) -> HashMap<u8,u8>{
+/// The chips in the outputs so far, by output.
+fn outputs(output: &HashMap<u8, u8>) -> String {
+    let mut chips: Vec<(&u8, &u8)> = output.iter().collect();
+    chips.sort();
+    chips.iter().map(|(id, value)| format!("{}={}", id, value)).collect::<Vec<String>>().join(" ")
+}
+
+/// Hands out the values and lets the bots pass chips around, returning the chip per output; when
+/// cancelled, the chips in the outputs so far are given.
+pub fn process(values: &[(u8, u8)], bots : &mut HashMap<u8, Bot>, cancel: &Cancel) -> Result<HashMap<u8,u8>> {
     let mut output= HashMap::new();
 
     for (value, bot_id) in values {
+        cancel.check(|| outputs(&output))?;
         // get a value and update the required bot
-        bots.get_mut(bot_id).unwrap().accept(*value);
+        bot(bots, *bot_id)?.accept(*value)?;
 
         // update all bots impacted, starting with the one accepted the value
         // use a queue as updates can impact new bots not impacted originally
         let mut updated = vec![*bot_id];
-        while !updated.is_empty() {
+        while let Some(from) = updated.pop() {
+            cancel.check(|| outputs(&output))?;
             // Get the bot values required for updating targets. This cannot be done using a borrow
             // as the compiler cannot ensure the mutable borrowed bot won;t change during the
             // target update ... yes sometimes this ownership thing is not straight forward
             let (value_1, value_2, target_id_low, target_id_high) = {
-                let b = bots.get(&updated.pop().unwrap()).unwrap();
-                (b.value_1.clone(), b.value_2.clone(), b.target_id_low.clone(), b.target_id_high.clone())
+                let b = bot(bots, from)?;
+                (b.value_1, b.value_2, b.target_id_low.clone(), b.target_id_high.clone())
             };
 
             // if the bot has two values ... then targets should be updated
-            if value_1.is_some() && value_2.is_some() {
-                let (value_low, value_high) = (value_1.unwrap(), value_2.unwrap());
+            if let (Some(value_low), Some(value_high)) = (value_1, value_2) {
 
                 // Update targets
                 for (id, value) in [(target_id_low, value_low), (target_id_high, value_high)] {
                     match id {
                         TargetId::Output(output_id) => {
+                            trace!(from, value, output = output_id, "hand off");
                             output.insert(output_id, value);
-                            () // required to ensure the match arms have the same result value
                         },
                         TargetId::Bot(bot_id) => {
-                            bots.get_mut(&bot_id).unwrap().accept(value);
-                            updated.push(bot_id);
-                            () // required to ensure the match arms have the same result value
+                            trace!(from, value, bot = bot_id, "hand off");
+                            bot(bots, bot_id)?.accept(value)?;
+                            // queued once, even when it gets both chips before its turn
+                            if !updated.contains(&bot_id) {
+                                updated.push(bot_id);
+                            }
                         },
                     };
                 }
@@ -144,51 +178,181 @@ fn process(values: &Vec<(u8, u8)>, bots : &mut HashMap<u8, Bot>) -> HashMap<u8,u
         }
     }
 
-    output
+    Ok(output)
+}
+
+/// The chips the wanted bot compares and the outputs multiplied, configured as `compare` and `outputs`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Setup {
+    /// The chips the bot of part 1 compares.
+    pub compare: (u8, u8),
+    /// The outputs of part 2.
+    pub outputs: Vec<u8>,
+}
+
+impl Default for Setup {
+    fn default() -> Self {
+        Setup { compare: (17, 61), outputs: vec![0, 1, 2] }
+    }
+}
+
+/// The bot comparing the `low` and `high` microchips.
+pub fn comparing_bot((values, bots): &Factory, low: u8, high: u8, cancel: &Cancel) -> Result<u8> {
+    let mut bots = bots.clone();
+    process(values, &mut bots, cancel)?;
+
+    bots.iter()
+        .find(|(_, v)| v.compares(low, high))
+        .map(|(key, _)| *key)
+        .ok_or_else(|| Error::NotFound(format!("bot comparing value-{} and value-{} microchips", low, high)))
+}
+
+/// Product of the chips in the given outputs.
+pub fn output_product((values, bots): &Factory, outputs: &[u8], cancel: &Cancel) -> Result<i32> {
+    let output = process(values, &mut bots.clone(), cancel)?;
+
+    let mut product = 1;
+    for id in outputs {
+        let value = output.get(id).ok_or_else(|| Error::NotFound(format!("chip in output {}", id)))?;
+        product = i32::from(*value).checked_mul(product)
+            .ok_or_else(|| Error::InvalidState("the product of the outputs overflows".to_string()))?;
+    }
+
+    Ok(product)
 }
 
-pub fn part1(input: &Vec<String>) -> Result<u8,&str> {
-    let (values, mut bots) = factory(input, 17, 61);
-    let _ = process(&values, &mut bots);
+/// The bot comparing value-17 with value-61 microchips.
+pub fn part1(input: &Factory) -> Result<u8> {
+    comparing_bot(input, 17, 61, &Cancel::none())
+}
 
-    let (key, _) = bots.iter().find(|(_, v)| v.processed == true).unwrap();
-    Ok(*key)
+/// Product of the chips in outputs 0, 1 and 2.
+pub fn part2(input: &Factory) -> Result<i32> {
+    output_product(input, &[0, 1, 2], &Cancel::none())
 }
 
-pub fn part2(input: &Vec<String>) -> Result<i32,&str> {
-    let (values, mut bots) = factory(input, 17, 61);
-    let output = process(&values, &mut bots);
+/// The [`Solution`] of day 10.
+pub struct Day10;
 
-    let a = *output.get(&0).unwrap() as i32;
-    let b = *output.get(&1).unwrap() as i32;
-    let c = *output.get(&2).unwrap() as i32;
+impl Solution for Day10 {
+    type Input = (Setup, Factory);
 
-    Ok(a * b * c)
+    fn parse(&self, input: &str) -> Result<(Setup, Factory)> {
+        Ok((Setup::default(), parse(input)?))
+    }
+
+    fn parse_with(&self, input: &str, params: &Params) -> Result<(Setup, Factory)> {
+        Ok((params.get()?, parse(input)?))
+    }
+
+    fn part1(&self, input: &(Setup, Factory)) -> Result<Answer> {
+        self.part1_with(input, &Cancel::none())
+    }
+
+    fn part2(&self, input: &(Setup, Factory)) -> Result<Answer> {
+        self.part2_with(input, &Cancel::none())
+    }
+
+    fn part1_with(&self, (setup, input): &(Setup, Factory), cancel: &Cancel) -> Result<Answer> {
+        let (low, high) = setup.compare;
+        comparing_bot(input, low.min(high), low.max(high), cancel).map(Answer::from)
+    }
+
+    fn part2_with(&self, (setup, input): &(Setup, Factory), cancel: &Cancel) -> Result<Answer> {
+        output_product(input, &setup.outputs, cancel).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
-    use crate::day10::{factory, process};
+    use crate::cancel::Cancel;
+    use crate::day10::{output_product, parse, process};
+    use crate::error::Error;
 
     #[rstest]
     fn test_process() {
-        let input = vec![
-            "value 5 goes to bot 2".to_string(),
-            "bot 2 gives low to bot 1 and high to bot 0".to_string(),
-            "value 3 goes to bot 1".to_string(),
-            "bot 1 gives low to output 1 and high to bot 0".to_string(),
-            "bot 0 gives low to output 2 and high to output 0".to_string(),
-            "value 2 goes to bot 2".to_string()
+        let input = [
+            "value 5 goes to bot 2",
+            "bot 2 gives low to bot 1 and high to bot 0",
+            "value 3 goes to bot 1",
+            "bot 1 gives low to output 1 and high to bot 0",
+            "bot 0 gives low to output 2 and high to output 0",
+            "value 2 goes to bot 2"
         ];
 
-        let (values, mut bots) = factory(&input, 2, 5);
-        let output = process(&values, &mut bots);
+        let (values, mut bots) = parse(&input.join("\n")).unwrap();
+        let output = process(&values, &mut bots, &Cancel::none()).unwrap();
         assert_eq!(output.get(&0).unwrap(), &5u8);
         assert_eq!(output.get(&1).unwrap(), &2u8);
         assert_eq!(output.get(&2).unwrap(), &3u8);
-        assert_eq!(bots.get(&0).unwrap().processed, false);
-        assert_eq!(bots.get(&1).unwrap().processed, false);
-        assert_eq!(bots.get(&2).unwrap().processed, true);
+        assert!(!bots.get(&0).unwrap().compares(2, 5));
+        assert!(!bots.get(&1).unwrap().compares(2, 5));
+        assert!(bots.get(&2).unwrap().compares(2, 5));
+    }
+
+    #[rstest]
+    #[case(vec!["value 5 goes to bot 2", "bot 2 gives low to bin 1 and high to bot 0"], 2, 20)]
+    #[case(vec!["value 500 goes to bot 2"], 1, 7)]
+    #[case(vec!["bot 2 gives low to bot 1 and high to bot 0", "bot 2 gives low to output 1 and high to bot 0"], 2, 1)]
+    fn test_parse_error(#[case] input: Vec<&str>, #[case] line: usize, #[case] column: usize) {
+        assert!(matches!(parse(&input.join("\n")), Err(Error::Parse { line: l, column: c, .. }) if l == line && c == column));
+    }
+
+    #[rstest]
+    fn test_process_unknown_bot() {
+        let input = ["value 5 goes to bot 2"];
+        let (values, mut bots) = parse(&input.join("\n")).unwrap();
+        assert!(matches!(process(&values, &mut bots, &Cancel::none()), Err(Error::NotFound(_))));
+    }
+
+    #[rstest]
+    fn test_process_both_chips_to_one_bot() {
+        let input = [
+            "value 5 goes to bot 0",
+            "value 3 goes to bot 0",
+            "bot 0 gives low to bot 1 and high to bot 1",
+            "bot 1 gives low to output 0 and high to bot 2",
+            "value 7 goes to bot 2",
+            "bot 2 gives low to output 1 and high to output 2",
+        ];
+        let (values, mut bots) = parse(&input.join("\n")).unwrap();
+        let output = process(&values, &mut bots, &Cancel::none()).unwrap();
+        assert_eq!((output.get(&0), output.get(&1), output.get(&2)), (Some(&3u8), Some(&5u8), Some(&7u8)));
+    }
+
+    #[rstest]
+    fn test_process_cycle() {
+        let input = [
+            "value 5 goes to bot 0",
+            "value 3 goes to bot 0",
+            "bot 0 gives low to bot 0 and high to output 0",
+        ];
+        let (values, mut bots) = parse(&input.join("\n")).unwrap();
+        assert!(matches!(process(&values, &mut bots, &Cancel::none()), Err(Error::InvalidState(_))));
+    }
+
+    #[rstest]
+    fn test_process_cancelled() {
+        let input = [
+            "value 5 goes to bot 0",
+            "value 3 goes to bot 0",
+            "bot 0 gives low to output 1 and high to output 0",
+        ];
+        let (values, mut bots) = parse(&input.join("\n")).unwrap();
+        let cancel = Cancel::none();
+        cancel.cancel();
+        assert!(matches!(process(&values, &mut bots, &cancel), Err(Error::TimedOut { partial: Some(p) }) if p.is_empty()));
+    }
+
+    #[rstest]
+    fn test_output_product_overflow() {
+        let input = [
+            "value 200 goes to bot 0",
+            "value 201 goes to bot 0",
+            "bot 0 gives low to output 0 and high to output 1",
+        ];
+        assert!(matches!(output_product(&parse(&input.join("\n")).unwrap(), &[0, 1, 0, 1, 0], &Cancel::none()), Err(Error::InvalidState(_))));
     }
 }
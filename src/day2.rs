@@ -1,96 +1,74 @@
-use euclid::Point2D;
-use grid::{Grid, grid};
-
-#[derive(Debug, Copy, Clone, PartialEq)]
-enum Command {
-    LEFT,
-    RIGHT,
-    UP,
-    DOWN,
-}
-
-impl Command {
-    fn from(command: char) -> Command {
-        match command {
-            'U' => Command::UP,
-            'D' => Command::DOWN,
-            'L' => Command::LEFT,
-            'R' => Command::RIGHT,
-            _ => panic!("Invalid command char '{}'", command)
-        }
+//! Day 2: Bathroom Security.
+
+use tracing::debug;
+use crate::error::{Error, Result};
+use crate::geometry::{Grid, Heading, Point};
+use crate::solution::{Answer, Solution};
+
+/// The moves on one line, one heading per `U`, `D`, `L` or `R`.
+fn moves(line: &str) -> Result<Vec<Heading>> {
+    let mut list: Vec<Heading> = Vec::new();
+    for (idx, ch) in line.chars().enumerate() {
+        let heading = Heading::from_arrow(ch)
+            .ok_or_else(|| Error::parse(idx + 1, format!("invalid command char '{}'", ch)))?;
+        list.push(heading);
     }
 
-    fn from_str(commands: &str) -> Vec<Command> {
-        let mut list: Vec<Command> = Vec::new();
-        for ch in commands.chars() {
-            list.push(Command::from(ch));
-        }
+    Ok(list)
+}
 
-        list
+fn moves_per_line<S: AsRef<str>>(lines: &[S]) -> Result<Vec<Vec<Heading>>> {
+    let mut list: Vec<Vec<Heading>> = Vec::new();
+    for (idx, line) in lines.iter().enumerate() {
+        list.push(moves(line.as_ref()).map_err(|e| e.at(idx + 1, 1))?);
     }
 
-    fn from_string_list(commands: &Vec<String>) -> Vec<Vec<Command>> {
-        let mut list: Vec<Vec<Command>> = Vec::new();
-        for line in commands {
-            list.push(Command::from_str(line));
-        }
-
-        list
-    }
+    Ok(list)
 }
 
+/// A keypad with the finger resting on one of its keys; blank keys cannot be moved to.
 #[derive(Debug, Clone)]
-struct KeyBoard {
+pub struct KeyBoard {
     grid: Grid<char>,
-    point: Point2D<usize, usize>,
+    point: Point<usize>,
 }
 
 impl KeyBoard {
-    fn new(grid: Grid<char>, point: Point2D<usize, usize>) -> KeyBoard {
-        let (x, y) = grid.size();
+    /// A keypad of an uneven size, with the finger on `point`.
+    pub fn new(grid: Grid<char>, point: Point<usize>) -> Result<KeyBoard> {
+        let (x, y) = (grid.width(), grid.height());
         if x % 2 == 0 || y % 2 == 0 {
-            panic!("The grid must be uneven in size ({},{})", x, y);
+            return Err(Error::InvalidState(format!("the grid must be uneven in size ({},{})", x, y)));
         }
 
-        if point.x >= x || point.y >= y {
-            panic!("The initial point is off the grid {:?}", point);
+        if !grid.contains(point) {
+            return Err(Error::InvalidState(format!("the initial point is off the grid {:?}", point)));
         }
 
-        KeyBoard {
+        Ok(KeyBoard {
             grid,
             point,
-        }
+        })
     }
 
-    fn at(&self) -> char {
-        *self.grid.get(self.point.y, self.point.x).unwrap()
+    /// The key under the finger.
+    pub fn at(&self) -> char {
+        *self.grid.get(self.point).unwrap()
     }
 
-    fn move_to(&mut self, command: &Command) -> char {
-        let (max_y, max_x) = self.grid.size();
-
-        let x = match command {
-            Command::LEFT => if self.point.x > 0 { self.point.x - 1 } else { 0 },
-            Command::RIGHT => if self.point.x < max_x - 1 { self.point.x + 1 } else { max_x - 1 },
-            _ => self.point.x
-        };
-        let y = match command {
-            Command::UP => if self.point.y > 0 { self.point.y - 1 } else { 0 },
-            Command::DOWN => if self.point.y < max_y - 1 { self.point.y + 1 } else { max_y - 1 },
-            _ => self.point.y
-        };
-
-
-        if let Some(c) = self.grid.get(y, x) {
-            if *c != ' ' {
-                self.point = Point2D::new(x, y);
+    /// Moves the finger to the next key in the heading, unless there is none, and gives the key.
+    pub fn move_to(&mut self, command: &Heading) -> char {
+        if let Some(point) = self.grid.next(self.point, *command) {
+            if self.grid.get(point) != Some(&' ') {
+                self.point = point;
             }
         }
 
         self.at()
     }
 
-    fn move_to_list(&mut self, commands: &Vec<Command>) -> char {
+    /// Follows all of the moves and gives the key the finger ends on.
+    pub fn move_to_list(&mut self, commands: &[Heading]) -> char {
         for command in commands {
             self.move_to(command);
         }
@@ -98,67 +76,91 @@ impl KeyBoard {
         self.at()
     }
 
-    fn move_to_list_of_list(&mut self, commands: &Vec<Vec<Command>>) -> String {
+    /// Follows every line of commands and collects the key pressed at the end of each line.
+    pub fn move_to_list_of_list(&mut self, commands: &[Vec<Heading>]) -> String {
         let mut result = String::new();
 
         for list in commands {
-            result.push(self.move_to_list(list));
+            let key = self.move_to_list(list);
+            debug!(%key, moves = list.len(), "pressed");
+            result.push(key);
         }
 
         result
     }
 }
 
-fn three_by_three() -> Grid<char> {
-    grid![
-        ['1','2','3']
-        ['4','5','6']
-        ['7','8','9']
-    ]
+fn keypad(rows: &[&str]) -> Grid<char> {
+    Grid::from_rows(rows.iter().map(|row| row.chars().collect()).collect()).unwrap()
+}
+
+/// The keypad of part 1.
+pub fn three_by_three() -> Grid<char> {
+    keypad(&["123", "456", "789"])
 }
 
-pub fn part1(input: &Vec<String>) -> String {
-    let mut keyboard = KeyBoard::new(three_by_three(), Point2D::new(1, 1));
-    let commands = Command::from_string_list(&input);
-    keyboard.move_to_list_of_list(&commands)
+/// Parses one line of commands per key of the code.
+pub fn parse(input: &str) -> Result<Vec<Vec<Heading>>> {
+    moves_per_line(&input.lines().collect::<Vec<&str>>())
 }
 
-fn five_by_five() -> Grid<char> {
-    grid![
-            [' ', ' ', '1', ' ', ' ']
-            [' ', '2', '3', '4', ' ']
-            ['5', '6', '7', '8', '9']
-            [' ', 'A', 'B', 'C', ' ']
-            [' ', ' ', 'D', ' ', ' ']
-        ]
+/// The bathroom code on the 3x3 keypad.
+pub fn part1(input: &[Vec<Heading>]) -> Result<String> {
+    let mut keyboard = KeyBoard::new(three_by_three(), Point::new(1, 1))?;
+    Ok(keyboard.move_to_list_of_list(input))
 }
 
-pub fn part2(input: &Vec<String>) -> String {
-    let mut keyboard = KeyBoard::new(five_by_five(), Point2D::new(0, 2));
-    let commands = Command::from_string_list(&input);
-    keyboard.move_to_list_of_list(&commands)
+/// The diamond shaped keypad of part 2.
+pub fn five_by_five() -> Grid<char> {
+    keypad(&["  1  ", " 234 ", "56789", " ABC ", "  D  "])
+}
+
+/// The bathroom code on the diamond shaped keypad.
+pub fn part2(input: &[Vec<Heading>]) -> Result<String> {
+    let mut keyboard = KeyBoard::new(five_by_five(), Point::new(0, 2))?;
+    Ok(keyboard.move_to_list_of_list(input))
+}
+
+/// The [`Solution`] of day 2.
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Vec<Heading>>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Vec<Heading>>> {
+        parse(input)
+    }
+
+    fn part1(&self, input: &Vec<Vec<Heading>>) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Vec<Vec<Heading>>) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use euclid::Point2D;
     use rstest::rstest;
-    use crate::day2::{Command, five_by_five, KeyBoard, three_by_three};
+    use crate::day2::{five_by_five, KeyBoard, moves, moves_per_line, parse, three_by_three};
+    use crate::error::Error;
+    use crate::geometry::{Heading, Point};
 
     #[rstest]
     fn test_commands() {
-        let commands = Command::from_str("ULDR");
+        let commands = moves("ULDR").unwrap();
 
         assert_eq!(4, commands.len());
-        assert_eq!(Command::UP, *commands.get(0).unwrap());
-        assert_eq!(Command::LEFT, *commands.get(1).unwrap());
-        assert_eq!(Command::DOWN, *commands.get(2).unwrap());
-        assert_eq!(Command::RIGHT, *commands.get(3).unwrap());
+        assert_eq!(Heading::North, *commands.first().unwrap());
+        assert_eq!(Heading::West, *commands.get(1).unwrap());
+        assert_eq!(Heading::South, *commands.get(2).unwrap());
+        assert_eq!(Heading::East, *commands.get(3).unwrap());
     }
 
     #[rstest]
     fn test_keyboard() {
-        let keyboard = KeyBoard::new(three_by_three(), Point2D::new(1, 1));
+        let keyboard = KeyBoard::new(three_by_three(), Point::new(1, 1)).unwrap();
         assert_eq!('5', keyboard.at());
     }
 
@@ -170,8 +172,8 @@ mod tests {
     #[case("DLL", '7')]
     #[case("DRR", '9')]
     fn test_keyboard_move_to_all(#[case] input: &str, #[case] expected: char) {
-        let mut keyboard = KeyBoard::new(three_by_three(), Point2D::new(1, 1));
-        let commands = Command::from_str(input);
+        let mut keyboard = KeyBoard::new(three_by_three(), Point::new(1, 1)).unwrap();
+        let commands = moves(input).unwrap();
 
         assert_eq!(expected, keyboard.move_to_list(&commands));
     }
@@ -184,12 +186,17 @@ mod tests {
             String::from("LURDL"),
             String::from("UUUUD"),
         ];
-        let commands = Command::from_string_list(&input);
+        let commands = moves_per_line(&input).unwrap();
 
-        let mut keyboard = KeyBoard::new(three_by_three(), Point2D::new(1, 1));
+        let mut keyboard = KeyBoard::new(three_by_three(), Point::new(1, 1)).unwrap();
         assert_eq!("1985", keyboard.move_to_list_of_list(&commands).as_str());
 
-        keyboard = KeyBoard::new(five_by_five(), Point2D::new(0, 2));
+        keyboard = KeyBoard::new(five_by_five(), Point::new(0, 2)).unwrap();
         assert_eq!("5DB3", keyboard.move_to_list_of_list(&commands).as_str());
     }
+
+    #[rstest]
+    fn test_parse_error() {
+        assert!(matches!(parse("ULL\nRRXDD"), Err(Error::Parse { line: 2, column: 3, .. })));
+    }
 }
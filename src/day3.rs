@@ -1,46 +1,69 @@
-fn to_vec(input: &String) -> Vec<i32> {
+//! Day 3: Squares With Three Sides.
+
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+
+fn to_vec(input: &str) -> Result<Vec<i32>> {
     let mut result: Vec<i32> = Vec::new();
-    for part in input.trim().split_whitespace() {
-        let side: i32 = part.trim().parse().unwrap();
-        result.push(side);
+    let mut column = 1;
+    for part in input.split(' ') {
+        if !part.is_empty() {
+            let side: i32 = part.parse()
+                .map_err(|_| Error::parse(column, format!("invalid side '{}'", part)))?;
+            result.push(side);
+        }
+        column += part.len() + 1;
     }
 
-    result
+    if result.len() != 3 {
+        return Err(Error::parse(1, format!("expected 3 sides, found {}", result.len())));
+    }
+
+    Ok(result)
 }
 
-fn to_vec_from_string_vec(input: &Vec<String>) -> Vec<Vec<i32>> {
-    input.iter().map(|s| to_vec(s)).collect()
+/// Parses the three side lengths on every line.
+pub fn parse(input: &str) -> Result<Vec<Vec<i32>>> {
+    input.lines()
+        .enumerate()
+        .map(|(idx, line)| to_vec(line).map_err(|e| e.at(idx + 1, 1)))
+        .collect()
 }
 
-fn sort_vec(v : &Vec<i32>) -> Vec<i32> {
-    let mut v2 = v.clone();
+fn sort_vec(v : &[i32]) -> Vec<i32> {
+    let mut v2 = v.to_vec();
     v2.sort();
     v2
 }
 
-fn possible(v: &Vec<i32>) -> bool {
-    let v2 = sort_vec(v);
-    *v2.get(0).unwrap() + *v2.get(1).unwrap() > *v2.get(2).unwrap()
+/// Whether the three sides can form a triangle.
+pub fn possible(v: &[i32]) -> bool {
+    let v2: Vec<i64> = sort_vec(v).into_iter().map(i64::from).collect();
+    v2[0] + v2[1] > v2[2]
 }
 
-pub fn part1(input: &Vec<String>) -> usize {
-    to_vec_from_string_vec(input)
-        .iter()
+/// Number of rows that are possible triangles.
+pub fn part1(input: &[Vec<i32>]) -> Result<usize> {
+    Ok(input.iter()
         .filter(|v| possible(v))
-        .count()
+        .count())
 }
 
-pub fn part2(input: &Vec<String>) -> usize {
-    let list = to_vec_from_string_vec(input);
+/// Number of possible triangles when reading columns of three rows.
+pub fn part2(input: &[Vec<i32>]) -> Result<usize> {
+    if !input.len().is_multiple_of(3) {
+        return Err(Error::InvalidState(format!("{} rows cannot be grouped vertically by three", input.len())));
+    }
+
     let mut count = 0;
 
     let mut y = 0;
-    while y < list.len() {
+    while y < input.len() {
         for x in 0..3 {
             let v = vec![
-                *list.get(y + 0).unwrap().get(x).unwrap(),
-                *list.get(y + 1).unwrap().get(x).unwrap(),
-                *list.get(y + 2).unwrap().get(x).unwrap(),
+                *input.get(y).unwrap().get(x).unwrap(),
+                *input.get(y + 1).unwrap().get(x).unwrap(),
+                *input.get(y + 2).unwrap().get(x).unwrap(),
             ];
 
             if possible(&v) {
@@ -50,18 +73,57 @@ pub fn part2(input: &Vec<String>) -> usize {
         y += 3;
     }
 
-    count
+    Ok(count)
+}
+
+/// The [`Solution`] of day 3.
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<Vec<i32>>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Vec<i32>>> {
+        parse(input)
+    }
+
+    fn part1(&self, input: &Vec<Vec<i32>>) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Vec<Vec<i32>>) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
-    use crate::day3::{to_vec};
+    use crate::day3::{parse, part1, part2, to_vec};
+    use crate::error::Error;
 
     #[rstest]
-    #[case("  2  3  1  ", vec ! [1, 2, 3])]
-    #[case("2 3 1", vec ! [1, 2, 3])]
+    #[case("  2  3  1  ", vec ! [2, 3, 1])]
+    #[case("2 3 1", vec ! [2, 3, 1])]
     fn test_to_vec(#[case] input: &str, #[case] expected: Vec<i32>) {
-        assert_eq!(expected, *to_vec(&String::from(input)));
+        assert_eq!(expected, to_vec(input).unwrap());
+    }
+
+    #[rstest]
+    #[case("5 10 25\n5 1x 25", 2, 3)]
+    #[case("5 10 25\n5 10", 2, 1)]
+    fn test_parse_error(#[case] input: &str, #[case] line: usize, #[case] column: usize) {
+        assert!(matches!(parse(input), Err(Error::Parse { line: l, column: c, .. }) if l == line && c == column));
+    }
+
+    #[rstest]
+    fn test_large_sides() {
+        let input = parse("2147483647 2147483647 2147483647\n1 2147483647 2147483647").unwrap();
+        assert_eq!(part1(&input).unwrap(), 2);
+    }
+
+    #[rstest]
+    fn test_part2_incomplete_group() {
+        let input = parse("101 301 501\n102 302 502").unwrap();
+        assert!(matches!(part2(&input), Err(Error::InvalidState(_))));
     }
 }
@@ -1,41 +1,52 @@
+//! Day 4: Security Through Obscurity.
+
 use std::cmp::Ordering::Equal;
 use std::collections::HashMap;
-use lazy_static::lazy_static;
-use regex::Regex;
+use tracing::trace;
+use crate::error::{Error, Result};
+use crate::parser::{delimited, identifier, Input, lines, literal, map, number, pair, parse_all, Parser, take_while1};
+use crate::solution::{Answer, Solution};
 
+/// An encrypted room like `aaaaa-bbb-z-y-x-123[abxyz]`.
 #[derive(Debug,PartialEq)]
-struct Room {
-    name: String,
-    sector_id: i32,
-    checksum: String,
+pub struct Room {
+    /// The encrypted name, with dashes between the words.
+    pub name: String,
+    /// The sector id, also the key of the shift cipher.
+    pub sector_id: i32,
+    /// The five letters claimed to be the most common in the name.
+    pub checksum: String,
 }
 
-impl Room {
-    fn from(input: &str) -> Result<Room, String> {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"([a-z\-]+)-(\d+)\[(\w+)\]").unwrap();
-        }
-
-        if let Some(caps) = RE.captures(input) {
-            let name = String::from(caps.get(1).map(|m| m.as_str()).unwrap());
-            let sector_id: i32 = caps.get(2).map(|m| m.as_str().parse::<i32>().unwrap()).unwrap();
-            let checksum = String::from(caps.get(3).map(|m| m.as_str()).unwrap());
-
-            return Ok(Room { name, sector_id, checksum });
-        }
+/// The dashed name, the sector id and the checksum in brackets.
+fn room() -> impl Parser<Room> {
+    let name = |input: &mut Input| {
+        let name = take_while1("a room name", |ch| ch.is_ascii_lowercase() || ch == '-').parse(input)?;
+        name.strip_suffix('-')
+            .map(String::from)
+            .ok_or_else(|| input.error("expected '-' before the sector id"))
+    };
+    let checksum = delimited(literal("["), identifier(), literal("]"));
+
+    map(pair(name, pair(number(), checksum)), |(name, (sector_id, checksum))| Room { name, sector_id, checksum })
+}
 
-        Err(format!("Invalid input {}", input))
+impl Room {
+    /// Parses a single room.
+    pub fn from(input: &str) -> Result<Room> {
+        parse_all(&room(), input)
     }
 
-    fn decrypt(&self) -> String {
+    /// The name shifted by the sector id, with dashes turned into spaces.
+    pub fn decrypt(&self) -> String {
         let secret = (self.sector_id % 26) as u8;
-        let a = 'a' as u8;
-        let z = 'z' as u8;
+        let a = b'a';
+        let z = b'z';
 
         self.name.chars()
             .map(|c| {
                 if c == '-' {
-                    return ' ';
+                    ' '
                 } else {
                     let i = (c as u8) + secret;
                     if i > z { (i - z + a - 1) as char } else { i as char }
@@ -44,66 +55,95 @@ impl Room {
             .collect::<String>()
     }
 
-    fn check(&self) -> bool {
-        let mut hash: HashMap<char, i32> = HashMap::new();
-        for ch in self.name.chars() {
-            if ch == '-' {
-                continue;
-            }
+    /// Whether the checksum holds the five most common letters of the name.
+    pub fn check(&self) -> bool {
+        checksum(&self.name) == self.checksum
+    }
+}
 
-            if !hash.contains_key(&ch) {
-                hash.insert(ch, 1);
-            }
-            if let Some(value) = hash.get_mut(&ch) {
-                *value += 1
-            }
+/// The five most common letters of the name, ties broken alphabetically.
+pub fn checksum(name: &str) -> String {
+    let mut hash: HashMap<char, i32> = HashMap::new();
+    for ch in name.chars() {
+        if ch == '-' {
+            continue;
         }
 
-        let mut list : Vec<(&char, &i32)> = hash.iter()
-            .map(|f| f)
-            .collect();
-        list.sort_by(|a,b| {
-            if b.1.cmp(a.1) != Equal {
-                b.1.cmp(a.1)
-            } else {
-                a.0.cmp(b.0)
-            }
-        });
-
-        let mut result = String::new();
-        for entry in list {
-            result.push(*entry.0);
-            if result.len() == 5 {
-                break;
-            }
+        *hash.entry(ch).or_insert(0) += 1;
+    }
+
+    let mut list : Vec<(&char, &i32)> = hash.iter()
+        .collect();
+    list.sort_by(|a,b| {
+        if b.1.cmp(a.1) != Equal {
+            b.1.cmp(a.1)
+        } else {
+            a.0.cmp(b.0)
         }
+    });
 
-        result.eq(&self.checksum)
+    let mut result = String::new();
+    for entry in list {
+        result.push(*entry.0);
+        if result.len() == 5 {
+            break;
+        }
     }
+
+    result
 }
 
-pub fn part1(input: &Vec<String>) -> i32 {
+/// Parses one room per line.
+pub fn parse(input: &str) -> Result<Vec<Room>> {
+    lines(&room(), input)
+}
+
+/// Sum of the sector ids of the real rooms.
+pub fn part1(input: &[Room]) -> Result<i32> {
     input.iter()
-        .map(|text| Room::from(text).unwrap())
         .filter(|r| r.check())
-        .map(|r| r.sector_id)
-        .sum()
+        .try_fold(0i32, |sum, r| sum.checked_add(r.sector_id))
+        .ok_or_else(|| Error::InvalidState("the sum of the sector ids overflows".to_string()))
 }
 
-pub fn part2(input: &Vec<String>) -> Result<i32, &str> {
-    for room in input.iter().map(|text| Room::from(text).unwrap()) {
-        if room.decrypt().starts_with( "northpole object") {
+/// Sector id of the room where North Pole objects are stored.
+pub fn part2(input: &[Room]) -> Result<i32> {
+    for room in input {
+        let name = room.decrypt();
+        trace!(%name, sector_id = room.sector_id, "decrypted");
+        if name.starts_with( "northpole object") {
             return Ok(room.sector_id);
         }
     }
 
-    Err("North Pole objects room not found")
+    Err(Error::NotFound("North Pole objects room".to_string()))
+}
+
+/// The [`Solution`] of day 4.
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Room>;
+
+    fn parse(&self, input: &str) -> Result<Vec<Room>> {
+        parse(input)
+    }
+
+    fn part1(&self, input: &Vec<Room>) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Vec<Room>) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
     use rstest::rstest;
-    use crate::day4::Room;
+    use crate::day4::{parse, part1, part2, Room};
+    use crate::error::Error;
 
     #[rstest]
     #[case("aaaaa-bbb-z-y-x-123[abxyz]", Room{name: "aaaaa-bbb-z-y-x".to_string(), sector_id: 123, checksum: "abxyz".to_string()})]
@@ -127,4 +167,31 @@ mod tests {
         let room = Room::from(&input).unwrap();
         assert_eq!(room.decrypt().as_str(), expected);
     }
+
+    #[rstest]
+    #[case("aaaaa-bbb-z-y-x-123[abxyz]\nnot a room", 2, 4)]
+    #[case("aaaaa-bbb-z-y-x-99999999999[abxyz]", 1, 17)]
+    fn test_parse_error(#[case] input: &str, #[case] line: usize, #[case] column: usize) {
+        assert!(matches!(parse(input), Err(Error::Parse { line: l, column: c, .. }) if l == line && c == column));
+    }
+
+    #[rstest]
+    fn test_part1_overflow() {
+        let input = parse("a-2147483647[a]\nb-1[b]").unwrap();
+        assert!(matches!(part1(&input), Err(Error::InvalidState(_))));
+    }
+
+    #[rstest]
+    fn test_part2_not_found() {
+        let input = parse("aaaaa-bbb-z-y-x-123[abxyz]").unwrap();
+        assert!(matches!(part2(&input), Err(Error::NotFound(_))));
+    }
+
+    proptest! {
+        #[test]
+        fn prop_decrypt_repeats_every_26(name in "[a-z]{1,8}(-[a-z]{1,8}){0,4}", sector_id in 0..1_000_000i32) {
+            let room = |sector_id| Room { name: name.clone(), sector_id, checksum: String::new() };
+            prop_assert_eq!(room(sector_id).decrypt(), room(sector_id + 26).decrypt());
+        }
+    }
 }
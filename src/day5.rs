@@ -1,32 +1,61 @@
-enum Strategy {
-    FIXED,
-    PICK,
+//! Day 5: How About a Nice Game of Chess?
+
+use tracing::debug;
+use crate::cancel::Cancel;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
+
+/// How the characters of the password are picked from the interesting hashes.
+pub enum Strategy {
+    /// The sixth character of a hash is the next character of the password.
+    Fixed,
+    /// The sixth character is the position, the seventh the character to put there.
+    Pick,
 }
 
-pub fn part1(input: &str) -> String {
-    password(input, Strategy::FIXED)
+/// The door id is the whole input.
+pub fn parse(input: &str) -> Result<String> {
+    let door_id = input.trim();
+    if door_id.is_empty() {
+        return Err(Error::parse(1, "missing door id"));
+    }
+
+    Ok(door_id.to_string())
 }
 
-pub fn part2(input: &str) -> String {
-    password(input, Strategy::PICK)
+/// The password built from the sixth character of each interesting hash.
+pub fn part1(input: &str) -> Result<String> {
+    password(input, Strategy::Fixed, &Cancel::none())
 }
 
+/// The password with the seventh character placed at the position of the sixth.
+pub fn part2(input: &str) -> Result<String> {
+    password(input, Strategy::Pick, &Cancel::none())
+}
 
-fn password(door_id: &str, strategy: Strategy) -> String {
+/// Searches the MD5 hashes of the door id and an increasing index for the password; when
+/// cancelled, the characters found so far are given with `_` for the missing ones.
+pub fn password(door_id: &str, strategy: Strategy, cancel: &Cancel) -> Result<String> {
     let mut password: Vec<char> = vec![' '; 8];
-    let mut index = -1;
+    let mut next: u64 = 0;
     let mut offset : usize = 0;
     let mut count = 0;
 
     loop {
-        index += 1;
+        let index = next;
+        next += 1;
+        if index.is_multiple_of(1024) {
+            cancel.check(|| password.iter().map(|ch| if *ch == ' ' { '_' } else { *ch }).collect())?;
+        }
+
         let key = format!("{}{}", door_id, index);
         let digest = md5::compute(&key);
         let digest_str = format!("{:x}", &digest);
 
         if digest_str.starts_with("00000") {
+            debug!(index, hash = %digest_str, "interesting hash");
             let mut ch = digest_str.chars().nth(5).unwrap();
-            if matches!(strategy, Strategy::PICK) {
+            if matches!(strategy, Strategy::Pick) {
                 let digit= ch.to_digit(10);
                 if digit.is_none() {
                     continue;
@@ -42,6 +71,7 @@ fn password(door_id: &str, strategy: Strategy) -> String {
 
             if password[offset] == ' ' {
                 password[offset] = ch;
+                debug!(password = %password.iter().collect::<String>(), "password character found");
                 offset += 1;
                 count += 1;
             }
@@ -52,23 +82,60 @@ fn password(door_id: &str, strategy: Strategy) -> String {
         }
     }
 
-    password.iter().collect::<String>()
+    Ok(password.iter().collect::<String>())
+}
+
+/// The [`Solution`] of day 5.
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = String;
+
+    fn parse(&self, input: &str) -> Result<String> {
+        parse(input)
+    }
+
+    fn part1(&self, input: &String) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &String) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
+
+    fn part1_with(&self, input: &String, cancel: &Cancel) -> Result<Answer> {
+        password(input, Strategy::Fixed, cancel).map(Answer::from)
+    }
+
+    fn part2_with(&self, input: &String, cancel: &Cancel) -> Result<Answer> {
+        password(input, Strategy::Pick, cancel).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
+    use crate::cancel::Cancel;
     use crate::day5::{password, Strategy};
+    use crate::error::Error;
 
     #[rstest]
     fn test_fixed() {
-        let password = password("abc", Strategy::FIXED);
+        let password = password("abc", Strategy::Fixed, &Cancel::none()).unwrap();
         assert_eq!("18f47a30", password);
     }
 
     #[rstest]
     fn test_pick() {
-        let password = password("abc", Strategy::PICK);
+        let password = password("abc", Strategy::Pick, &Cancel::none()).unwrap();
         assert_eq!("05ace8e3", password);
     }
+
+    #[rstest]
+    fn test_cancelled() {
+        let cancel = Cancel::none();
+        cancel.cancel();
+        let password = password("abc", Strategy::Fixed, &cancel);
+        assert!(matches!(password, Err(Error::TimedOut { partial: Some(p) }) if p == "________"));
+    }
 }
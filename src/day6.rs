@@ -1,12 +1,16 @@
+//! Day 6: Signals and Noise.
+
 use std::cmp::Ordering;
 use std::collections::HashMap;
+use crate::error::{Error, Result};
+use crate::solution::{Answer, Solution};
 
-fn count_char(input : &Vec<String>) -> Vec<HashMap<char,i32>> {
+/// Counts the characters on each position of the messages.
+pub fn count_char(input : &[String]) -> Vec<HashMap<char,i32>> {
     let mut count : Vec<HashMap<char,i32>> = Vec::new();
 
     for word in input {
-        let mut pos = 0;
-        for ch in word.chars() {
+        for (pos, ch) in word.chars().enumerate() {
             if pos == count.len() {
                 count.push(HashMap::new());
             }
@@ -14,15 +18,14 @@ fn count_char(input : &Vec<String>) -> Vec<HashMap<char,i32>> {
             map.entry(ch)
                 .and_modify(|counter| *counter += 1)
                 .or_insert(1);
-
-            pos += 1;
         }
     }
 
     count
 }
 
-fn compile_message(count : &Vec<HashMap<char,i32>>, compare: fn(&i32, &i32) -> Ordering) -> String {
+/// Picks the first character per position after sorting the counts with `compare`.
+pub fn compile_message(count : &[HashMap<char,i32>], compare: fn(&i32, &i32) -> Ordering) -> String {
     let mut result = String::new();
 
     for map in count {
@@ -30,20 +33,51 @@ fn compile_message(count : &Vec<HashMap<char,i32>>, compare: fn(&i32, &i32) -> O
             .collect();
         list.sort_by(|a, b| compare(a.1, b.1));
 
-        result.push(*list.get(0).unwrap().0);
+        result.push(*list.first().unwrap().0);
     }
 
     result
 }
 
-pub fn part1(input: &Vec<String>) -> String {
-    let count = count_char(&input);
-    compile_message(&count, |a, b| b.cmp(a))
+/// Parses one repeated message per line.
+pub fn parse(input: &str) -> Result<Vec<String>> {
+    let lines: Vec<String> = input.lines().map(String::from).collect();
+    if lines.is_empty() {
+        return Err(Error::parse(1, "no messages received"));
+    }
+
+    Ok(lines)
+}
+
+/// The message built from the most common character per position.
+pub fn part1(input: &[String]) -> Result<String> {
+    let count = count_char(input);
+    Ok(compile_message(&count, |a, b| b.cmp(a)))
+}
+
+/// The message built from the least common character per position.
+pub fn part2(input: &[String]) -> Result<String> {
+    let count = count_char(input);
+    Ok(compile_message(&count, |a, b| a.cmp(b)))
 }
 
-pub fn part2(input: &Vec<String>) -> String {
-    let count = count_char(&input);
-    compile_message(&count, |a, b| a.cmp(b))
+/// The [`Solution`] of day 6.
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Vec<String>;
+
+    fn parse(&self, input: &str) -> Result<Vec<String>> {
+        parse(input)
+    }
+
+    fn part1(&self, input: &Vec<String>) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Vec<String>) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
@@ -1,46 +1,47 @@
+//! Day 7: Internet Protocol Version 7.
+
 use std::collections::HashSet;
-use lazy_static::lazy_static;
-use regex::Regex;
+use std::fmt;
+use crate::error::Result;
+use crate::parser::{delimited, letters, lines, literal, many, map, pair, parse_all, Parser};
+use crate::solution::{Answer, Solution};
 
+/// An IPv7 address, split into its parts; the odd parts are the hypernet sequences.
 #[derive(Debug, PartialEq)]
-struct IP7 {
+pub struct IP7 {
     sub: Vec<String>,
 }
 
-impl IP7 {
-    fn from(input: &String) -> Result<IP7, String> {
-        lazy_static! {
-            static ref RE: Regex = Regex::new(r"\w+").unwrap();
-        }
-
-        let mut sub: Vec<String> = Vec::new();
-        for m in RE.find_iter(input) {
-            sub.push(m.as_str().to_string());
-        }
+/// Letters, each following hypernet sequence in brackets followed by letters again.
+fn address() -> impl Parser<IP7> {
+    let hypernet = pair(delimited(literal("["), letters(), literal("]")), letters());
 
-        if sub.is_empty() {
-            return Err(format!("Invalid input {}", input));
+    map(pair(letters(), many(hypernet)), |(first, rest)| {
+        let mut sub = vec![first];
+        for (hypernet, supernet) in rest {
+            sub.push(hypernet);
+            sub.push(supernet);
         }
+        IP7 { sub }
+    })
+}
 
-        return Ok(IP7 { sub });
+impl IP7 {
+    /// Parses a single address.
+    pub fn from(input: &str) -> Result<IP7> {
+        parse_all(&address(), input)
     }
 
-    fn is_abba(part: &String) -> bool {
+    fn is_abba(part: &str) -> bool {
         let list: Vec<char> = part.chars().collect();
-
-        for idx in 0..list.len() - 3 {
-            if list[idx] == list[idx + 3] && list[idx + 1] == list[idx + 2] && list[idx] != list[idx + 1] {
-                return true;
-            }
-        }
-
-        false
+        list.windows(4).any(|w| w[0] == w[3] && w[1] == w[2] && w[0] != w[1])
     }
 
-    fn supports_tls(&self) -> bool {
+    /// An ABBA outside, and none inside, the hypernet sequences.
+    pub fn supports_tls(&self) -> bool {
         let mut i = 1;
         while i < self.sub.len() {
-            if IP7::is_abba(&self.sub.get(i).unwrap()) {
+            if IP7::is_abba(self.sub.get(i).unwrap()) {
                 return false;
             }
             i += 2;
@@ -48,7 +49,7 @@ impl IP7 {
 
         i = 0;
         while i < self.sub.len() {
-            if IP7::is_abba(&self.sub.get(i).unwrap()) {
+            if IP7::is_abba(self.sub.get(i).unwrap()) {
                 return true;
             }
             i += 2;
@@ -57,51 +58,37 @@ impl IP7 {
         false
     }
 
-    fn find_aba(part : &String, abas : &mut HashSet<String>)  {
+    fn find_aba(part : &str, abas : &mut HashSet<String>)  {
         let list: Vec<char> = part.chars().collect();
 
-        for idx in 0..list.len() - 2 {
-            if list[idx] == list[idx + 2] && list[idx] != list[idx + 1] {
-                let mut aba = String::new();
-                aba.push(list[idx]);
-                aba.push(list[idx+1]);
-                aba.push(list[idx+2]);
-                abas.insert(aba);
+        for w in list.windows(3) {
+            if w[0] == w[2] && w[0] != w[1] {
+                abas.insert(w.iter().collect());
             }
         }
     }
 
-    fn has_bab(part : &String, abas : &HashSet<String>) -> bool {
+    fn has_bab(part : &str, abas : &HashSet<String>) -> bool {
         let list: Vec<char> = part.chars().collect();
 
-        for idx in 0..list.len() - 2 {
-            if list[idx] == list[idx + 2] && list[idx] != list[idx + 1] {
-                let mut aba = String::new();
-                aba.push(list[idx+1]);
-                aba.push(list[idx]);
-                aba.push(list[idx+1]);
-
-                if abas.contains(&aba) {
-                    return true;
-                }
-            }
-        }
-
-        false
+        list.windows(3)
+            .filter(|w| w[0] == w[2] && w[0] != w[1])
+            .any(|w| abas.contains(&[w[1], w[0], w[1]].iter().collect::<String>()))
     }
 
-    fn supports_ssl(&self) -> bool {
+    /// An ABA outside the hypernet sequences with a matching BAB inside one.
+    pub fn supports_ssl(&self) -> bool {
         let mut abas : HashSet<String> = HashSet::new();
 
         let mut i = 0;
         while i < self.sub.len() {
-            IP7::find_aba(&self.sub.get(i).unwrap(), &mut abas);
+            IP7::find_aba(self.sub.get(i).unwrap(), &mut abas);
             i += 2;
         }
 
         i = 1;
         while i < self.sub.len() {
-            if IP7::has_bab(&self.sub.get(i).unwrap(), &abas) {
+            if IP7::has_bab(self.sub.get(i).unwrap(), &abas) {
                 return true;
             }
             i += 2;
@@ -112,52 +99,71 @@ impl IP7 {
 }
 
 
-pub fn part1(input: &Vec<String>) -> usize {
-    input.iter()
-        .map(|text| IP7::from(text).unwrap())
+/// Parses one address per line.
+pub fn parse(input: &str) -> Result<Vec<IP7>> {
+    lines(&address(), input)
+}
+
+/// Number of addresses supporting TLS.
+pub fn part1(input: &[IP7]) -> Result<usize> {
+    Ok(input.iter()
         .filter(|ip| ip.supports_tls())
-        .count()
+        .count())
 }
 
-pub fn part2(input: &Vec<String>) -> usize {
-    input.iter()
-        .map(|text| IP7::from(text).unwrap())
+/// Number of addresses supporting SSL.
+pub fn part2(input: &[IP7]) -> Result<usize> {
+    Ok(input.iter()
         .filter(|ip| ip.supports_ssl())
-        .count()
+        .count())
 }
 
-#[cfg(test)]
-impl IP7 {
-    fn to_string(&self) -> String {
-        let mut str = String::new();
-
+impl fmt::Display for IP7 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         let mut hyper = false;
         for s in &self.sub {
             if hyper {
-                str.push('[');
-            }
-            str.push_str(&s);
-            if hyper {
-                str.push(']');
+                write!(f, "[{}]", s)?;
+            } else {
+                write!(f, "{}", s)?;
             }
             hyper = !hyper;
         }
 
-        str
+        Ok(())
+    }
+}
+
+/// The [`Solution`] of day 7.
+pub struct Day7;
+
+impl Solution for Day7 {
+    type Input = Vec<IP7>;
+
+    fn parse(&self, input: &str) -> Result<Vec<IP7>> {
+        parse(input)
     }
 
+    fn part1(&self, input: &Vec<IP7>) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &Vec<IP7>) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use rstest::rstest;
-    use crate::day7::IP7;
+    use crate::day7::{IP7, parse};
+    use crate::error::Error;
 
     #[rstest]
     #[case("abba[mnop]qrst")]
     #[case("abcd[bddb]xyyx")]
     fn test_from(#[case] input: &str) {
-        assert_eq!(input.to_string(), IP7::from(&input.to_string()).unwrap().to_string());
+        assert_eq!(input.to_string(), IP7::from(input).unwrap().to_string());
     }
 
     #[rstest]
@@ -167,8 +173,10 @@ mod tests {
     #[case("abcd[bddb]xyyx", false)]
     #[case("aaaa[qwer]tyui", false)]
     #[case("ioxxoj[asdfgh]zxcvbn", true)]
+    #[case("a[b]c", false)]
+    #[case("ab[bc]abba", true)]
     fn test_is_abba(#[case] input: &str, #[case] expected: bool) {
-        assert_eq!(IP7::from(&input.to_string()).unwrap().supports_tls(), expected);
+        assert_eq!(IP7::from(input).unwrap().supports_tls(), expected);
     }
 
     #[rstest]
@@ -176,7 +184,14 @@ mod tests {
     #[case("xyx[xyx]xyx", false)]
     #[case("aaa[kek]eke", true)]
     #[case("zazbz[bzb]cdb", true)]
+    #[case("a[b]aba", false)]
+    #[case("aba[ba]xy", false)]
     fn test_has_bab(#[case] input : &str, #[case] expected : bool) {
-        assert_eq!(IP7::from(&input.to_string()).unwrap().supports_ssl(), expected);
+        assert_eq!(IP7::from(input).unwrap().supports_ssl(), expected);
     }
-}
\ No newline at end of file
+
+    #[rstest]
+    fn test_parse_error() {
+        assert!(matches!(parse("abba[mnop]qrst\n[]"), Err(Error::Parse { line: 2, column: 1, .. })));
+    }
+}
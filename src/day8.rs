@@ -1,184 +1,234 @@
-use std::fmt;
-use lazy_static::lazy_static;
-use regex::Regex;
+//! Day 8: Two-Factor Authentication.
 
+use std::fmt;
+use crate::parser::{alt, lines, literal, map, number, pair, parse_all, Parser, preceded};
+use serde::Deserialize;
+use tracing::trace;
+use crate::config::Params;
+use crate::error::{Error, Result};
+use crate::geometry::{Grid, Point};
+use crate::solution::{Answer, Solution};
+
+/// An operation on the screen.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Command {
-    Rect { dx: usize, dy: usize },
-    RotateRow { y: usize, dx: usize },
-    RotateColumn { x: usize, dy: usize },
+    /// `rect 3x2`: turns on the top-left rectangle.
+    Rect {
+        /// The width of the rectangle.
+        dx: usize,
+        /// The height of the rectangle.
+        dy: usize,
+    },
+    /// `rotate row y=0 by 4`: shifts a row to the right.
+    RotateRow {
+        /// The row.
+        y: usize,
+        /// The number of pixels to shift by.
+        dx: usize,
+    },
+    /// `rotate column x=1 by 1`: shifts a column down.
+    RotateColumn {
+        /// The column.
+        x: usize,
+        /// The number of pixels to shift by.
+        dy: usize,
+    },
 }
 
-impl Command {
-    pub fn from(input: &String) -> Result<Command, String> {
-        lazy_static! {
-            static ref RECT: Regex = Regex::new(r"rect (\d+)x(\d+)").unwrap();
-            static ref ROTATE_COLUMN: Regex = Regex::new(r"rotate column x=(\d+) by (\d+)").unwrap();
-            static ref ROTATE_ROW: Regex = Regex::new(r"rotate row y=(\d+) by (\d+)").unwrap();
-        }
-
-        if input.starts_with("rect ") {
-            if let Some(caps) = RECT.captures(&input) {
-                let dx = caps.get(1).map(|m| m.as_str()).unwrap().parse::<usize>().unwrap();
-                let dy = caps.get(2).map(|m| m.as_str()).unwrap().parse::<usize>().unwrap();
-
-                return Ok(Command::Rect { dx, dy });
-            }
-        }
-
-        if input.starts_with("rotate column ") {
-            if let Some(caps) = ROTATE_COLUMN.captures(&input) {
-                let x = caps.get(1).map(|m| m.as_str()).unwrap().parse::<usize>().unwrap();
-                let dy = caps.get(2).map(|m| m.as_str()).unwrap().parse::<usize>().unwrap();
-
-                return Ok(Command::RotateColumn { x, dy });
-            }
-        }
-
-        if input.starts_with("rotate row ") {
-            if let Some(caps) = ROTATE_ROW.captures(&input) {
-                let y = caps.get(1).map(|m| m.as_str()).unwrap().parse::<usize>().unwrap();
-                let dx = caps.get(2).map(|m| m.as_str()).unwrap().parse::<usize>().unwrap();
+/// Two numbers, each after its own literal.
+fn numbers(first: &'static str, second: &'static str) -> impl Parser<(usize, usize)> {
+    pair(preceded(literal(first), number()), preceded(literal(second), number()))
+}
 
-                return Ok(Command::RotateRow { y, dx });
-            }
-        }
+fn command() -> impl Parser<Command> {
+    alt((
+        map(numbers("rect ", "x"), |(dx, dy)| Command::Rect { dx, dy }),
+        map(numbers("rotate row y=", " by "), |(y, dx)| Command::RotateRow { y, dx }),
+        map(numbers("rotate column x=", " by "), |(x, dy)| Command::RotateColumn { x, dy }),
+    ))
+}
 
-        Err(format!("Cannot convert {} into command", input))
+impl Command {
+    /// Parses a single command.
+    pub fn from(input: &str) -> Result<Command> {
+        parse_all(&command(), input)
     }
 }
 
+/// The little screen on the door, pixels are either on (`#`) or off (`.`).
 #[derive(Debug, Clone)]
-struct Screen {
-    pixels: Vec<Vec<char>>,
+pub struct Screen {
+    pixels: Grid<char>,
 }
 
 const ON: char = '#';
 const OFF: char = '.';
 
+impl Default for Screen {
+    fn default() -> Self {
+        Screen::new()
+    }
+}
+
 impl Screen {
-    fn new() -> Screen {
+    /// A screen of 50 by 6 pixels, all off.
+    pub fn new() -> Screen {
+        Screen::with_size(50, 6)
+    }
+
+    /// A screen of `x` by `y` pixels, all off.
+    pub fn with_size(x: usize, y: usize) -> Screen {
         Screen {
-            pixels: vec![vec![OFF; 50]; 6],
+            pixels: Grid::new(x, y, OFF),
         }
     }
 
-    fn rect(&mut self, dx: usize, dy: usize) -> Result<(),String>{
-        if dx > self.pixels.get(0).unwrap().len() {
-            return Err(format!("Invalid value {} for dx, must be in range 0..{}", dx, self.pixels.get(0).unwrap().len()));
+    /// The number of pixels in a row.
+    pub fn width(&self) -> usize {
+        self.pixels.width()
+    }
+
+    /// The number of pixels in a column.
+    pub fn height(&self) -> usize {
+        self.pixels.height()
+    }
+
+    /// Turns on the pixels of the top-left rectangle of `dx` by `dy`.
+    pub fn rect(&mut self, dx: usize, dy: usize) -> Result<()>{
+        if dx > self.pixels.width() {
+            return Err(Error::InvalidState(format!("invalid value {} for dx, must be in range 0..{}", dx, self.pixels.width())));
         }
 
-        if dy > self.pixels.len() {
-            return Err(format!("Invalid value {} for dy, must be in range 0..{}", dy, self.pixels.len()));
+        if dy > self.pixels.height() {
+            return Err(Error::InvalidState(format!("invalid value {} for dy, must be in range 0..{}", dy, self.pixels.height())));
         }
 
         for y in 0..dy {
             for x in 0..dx {
-                self.pixels[y][x] = ON;
+                self.pixels.set(Point::new(x, y), ON)?;
             }
         }
 
         Ok(())
     }
 
-    fn on_count(&self) -> usize {
-        self.pixels.iter().map(|row| row.iter().filter(|p| **p == ON).count()).sum()
+    /// The number of pixels that are on.
+    pub fn on_count(&self) -> usize {
+        self.pixels.count(|p| *p == ON)
     }
 
-    fn rotate_row(&mut self, y: usize, mut dx: usize) -> Result<(), String> {
-        let len = self.pixels.len();
-        if y >= len {
-            return Err(format!("Invalid row {}, must be 0..{}", y, len));
-        }
-
-        let row = self.pixels.get_mut(y).unwrap();
-        let max = row.len() - 1;
-        while dx > 0 {
-            let tmp = *row.get(max).unwrap();
-            for x in (1..row.len()).rev() {
-                *row.get_mut(x).unwrap() = *row.get_mut(x - 1).unwrap();
-            }
-            *row.get_mut(0).unwrap() = tmp;
-            dx -= 1;
-        }
-
-        Ok(())
+    /// Shifts row `y` right by `dx` pixels, wrapping around.
+    pub fn rotate_row(&mut self, y: usize, dx: usize) -> Result<()> {
+        self.pixels.rotate_row(y, dx)
     }
 
-
-    fn rotate_column(&mut self, x: usize, mut dy: usize) -> Result<(), String> {
-        let len = self.pixels.get(0).unwrap().len();
-        if x >= len {
-            return Err(format!("Invalid column {}, must be 0..{}", x, len));
-        }
-
-        let max = self.pixels.len() - 1;
-        while dy > 0 {
-            let tmp = *self.pixels.get(max).unwrap().get(x).unwrap();
-
-            for y in (1..self.pixels.len()).rev() {
-                *self.pixels.get_mut(y).unwrap().get_mut(x).unwrap() = *self.pixels.get(y - 1).unwrap().get(x).unwrap();
-            }
-            *self.pixels.get_mut(0).unwrap().get_mut(x).unwrap() = tmp;
-            dy -= 1;
-        }
-
-        Ok(())
+    /// Shifts column `x` down by `dy` pixels, wrapping around.
+    pub fn rotate_column(&mut self, x: usize, dy: usize) -> Result<()> {
+        self.pixels.rotate_column(x, dy)
     }
 }
 
 impl fmt::Display for Screen {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        for row in &self.pixels {
-            write!(f, "{}\n", row.iter().collect::<String>()).expect("unable to display Screen");
-        }
+        write!(f, "{}", self.pixels)
+    }
+}
 
-        Ok(())
+impl From<Screen> for Answer {
+    fn from(screen: Screen) -> Answer {
+        Answer::Screen(screen.to_string())
     }
 }
 
-fn process(input: &Vec<Command>) -> Screen {
-    let mut screen = Screen::new();
+/// The size of the screen, configured as `width` and `height`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Size {
+    /// The number of pixels in a row.
+    pub width: usize,
+    /// The number of pixels in a column.
+    pub height: usize,
+}
 
+impl Default for Size {
+    fn default() -> Self {
+        Size { width: 50, height: 6 }
+    }
+}
+
+/// Applies the commands to a new screen.
+pub fn process(input: &[Command]) -> Result<Screen> {
+    apply(Screen::new(), input)
+}
+
+/// Applies the commands to the given screen.
+pub fn apply(mut screen: Screen, input: &[Command]) -> Result<Screen> {
     for command in input {
         match command {
             Command::Rect{dx, dy} => screen.rect(*dx, *dy),
             Command::RotateRow {y, dx} => screen.rotate_row(*y, *dx),
             Command::RotateColumn {x, dy} => screen.rotate_column(*x, *dy),
-        }.expect(format!("Invalid command {:?}", command).as_str());
+        }?;
+        trace!(?command, "applied\n{}", screen);
     }
 
-    screen
+    Ok(screen)
 }
 
+/// Parses one command per line.
+pub fn parse(input: &str) -> Result<Vec<Command>> {
+    lines(&command(), input)
+}
 
-pub fn part1(input: &Vec<Command>) -> usize {
-    let screen = process(input);
-    screen.on_count()
+/// Number of pixels lit after all commands.
+pub fn part1(input: &[Command]) -> Result<usize> {
+    let screen = process(input)?;
+    Ok(screen.on_count())
 }
 
-pub fn part2(input: &Vec<Command>) -> String {
-    let screen = process(input);
-    screen.to_string()
+/// The rendered screen after all commands.
+pub fn part2(input: &[Command]) -> Result<String> {
+    let screen = process(input)?;
+    Ok(screen.to_string())
 }
 
-#[cfg(test)]
-impl Screen {
-    fn with_size(x: usize, y: usize) -> Screen {
-        Screen {
-            pixels: vec![vec![OFF; x]; y],
+/// The [`Solution`] of day 8.
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = (Size, Vec<Command>);
+
+    fn parse(&self, input: &str) -> Result<(Size, Vec<Command>)> {
+        Ok((Size::default(), parse(input)?))
+    }
+
+    fn parse_with(&self, input: &str, params: &Params) -> Result<(Size, Vec<Command>)> {
+        let size: Size = params.get()?;
+        if size.width == 0 || size.height == 0 {
+            return Err(Error::InvalidState(format!("invalid screen size {}x{}", size.width, size.height)));
         }
+        Ok((size, parse(input)?))
+    }
+
+    fn part1(&self, (size, input): &(Size, Vec<Command>)) -> Result<Answer> {
+        apply(Screen::with_size(size.width, size.height), input).map(|screen| screen.on_count().into())
+    }
+
+    fn part2(&self, (size, input): &(Size, Vec<Command>)) -> Result<Answer> {
+        apply(Screen::with_size(size.width, size.height), input).map(Answer::from)
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
     use rstest::rstest;
-    use crate::day8::{Command, Screen};
+    use crate::day8::{apply, Command, parse, part1, Screen};
+    use crate::error::Error;
 
     #[rstest]
     fn test_display() {
-        println!("{}", Screen::new());
+        assert_eq!(Screen::new().to_string(), format!("{}\n", ".".repeat(50)).repeat(6));
         assert_eq!(Screen::new().on_count(), 0);
     }
 
@@ -187,7 +237,6 @@ mod tests {
         let mut screen = Screen::new();
         screen.rect(3, 2).expect("invalid rect");
         assert_eq!(screen.on_count(), 6);
-        println!("{}", screen);
     }
 
     #[rstest]
@@ -195,28 +244,71 @@ mod tests {
         let mut screen = Screen::with_size(7, 3);
         screen.rect(3, 2).expect("invalid rect");
         screen.rotate_column(1, 1).expect("could not rotate column");
-        println!("{}", screen);
+        assert_eq!(screen.to_string(), "#.#....\n###....\n.#.....\n");
         screen.rotate_row(0, 4).expect("could not rotate row");
-        println!("{}", screen);
+        assert_eq!(screen.to_string(), "....#.#\n###....\n.#.....\n");
         screen.rotate_column(1, 1).expect("could not rotate column");
-        println!("{}", screen);
+        assert_eq!(screen.to_string(), ".#..#.#\n#.#....\n.#.....\n");
     }
 
     #[rstest]
     fn test_command_rect() {
-        let command = Command::from(&"rect 3x2".to_string());
+        let command = Command::from("rect 3x2");
         assert_eq!(command.unwrap(), Command::Rect {dx : 3, dy : 2})
     }
 
     #[rstest]
     fn test_command_rotate_row() {
-        let command = Command::from(&"rotate row y=2 by 7".to_string());
+        let command = Command::from("rotate row y=2 by 7");
         assert_eq!(command.unwrap(), Command::RotateRow {y : 2, dx : 7})
     }
 
     #[rstest]
     fn test_command_rotate_column() {
-        let command = Command::from(&"rotate column x=12 by 3".to_string());
+        let command = Command::from("rotate column x=12 by 3");
         assert_eq!(command.unwrap(), Command::RotateColumn{x : 12, dy : 3})
     }
+
+    #[rstest]
+    #[case("rect 3x2\nrotate row y=x by 7", 2, 14)]
+    #[case("rect 3x2\nrotate diagonal", 2, 1)]
+    #[case("rect 3x99999999999999999999", 1, 8)]
+    fn test_parse_error(#[case] input: &str, #[case] line: usize, #[case] column: usize) {
+        assert!(matches!(parse(input), Err(Error::Parse { line: l, column: c, .. }) if l == line && c == column));
+    }
+
+    #[rstest]
+    fn test_invalid_command() {
+        let input = parse("rect 3x2\nrotate row y=6 by 1").unwrap();
+        assert!(matches!(part1(&input), Err(Error::InvalidState(_))));
+    }
+
+    /// A screen of 1 to 12 by 1 to 6 pixels after a few random commands.
+    fn screen() -> impl Strategy<Value = Screen> {
+        (1..12usize, 1..6usize).prop_flat_map(|(width, height)| {
+            let command = prop_oneof![
+                (0..=width, 0..=height).prop_map(|(dx, dy)| Command::Rect { dx, dy }),
+                (0..height, 0..30usize).prop_map(|(y, dx)| Command::RotateRow { y, dx }),
+                (0..width, 0..30usize).prop_map(|(x, dy)| Command::RotateColumn { x, dy }),
+            ];
+            prop::collection::vec(command, 0..10)
+                .prop_map(move |commands| apply(Screen::with_size(width, height), &commands).unwrap())
+        })
+    }
+
+    proptest! {
+        #[test]
+        fn prop_rotate_row_by_width_is_identity(screen in screen(), y in 0..6usize) {
+            let mut rotated = screen.clone();
+            rotated.rotate_row(y % screen.height(), screen.width()).unwrap();
+            prop_assert_eq!(rotated.to_string(), screen.to_string());
+        }
+
+        #[test]
+        fn prop_rotate_column_by_height_is_identity(screen in screen(), x in 0..12usize) {
+            let mut rotated = screen.clone();
+            rotated.rotate_column(x % screen.width(), screen.height()).unwrap();
+            prop_assert_eq!(rotated.to_string(), screen.to_string());
+        }
+    }
 }
@@ -1,75 +1,114 @@
-use std::str::Chars;
-use lazy_static::lazy_static;
-use regex::Regex;
+//! Day 9: Explosives in Cyberspace.
 
-pub fn repetition(input: String) -> Result<(usize, usize), String> {
-    lazy_static! {
-            static ref XY: Regex = Regex::new(r"(\d+)x(\d+)").unwrap();
-        }
+use tracing::trace;
+use crate::error::{Error, Result};
+use crate::parser::{delimited, Input, literal, number, pair, parse_all, Parser, preceded};
+use crate::solution::{Answer, Solution};
 
-    if let Some(caps) = XY.captures(&input) {
-        let length = caps.get(1).map(|m| m.as_str()).unwrap().parse::<usize>().unwrap();
-        let times = caps.get(2).map(|m| m.as_str()).unwrap().parse::<usize>().unwrap();
+/// The `AxB` contents of a marker: the number of characters to repeat and how often.
+fn marker_contents() -> impl Parser<(usize, usize)> {
+    pair(number(), preceded(literal("x"), number()))
+}
 
-        return Ok((length, times));
-    }
+/// Parses the `AxB` contents of a marker into its length and repeat count.
+pub fn repetition(input: String) -> Result<(usize, usize)> {
+    parse_all(&marker_contents(), &input)
+}
 
-    Err(format!("Cannot convert {} into ?x?", input))
+fn overflow() -> Error {
+    Error::InvalidState("the decompressed length overflows".to_string())
 }
 
-fn take(iter: &mut Chars, mut length: usize) -> String {
-    let mut taken = String::new();
-    while length > 0 {
-        if let Some(ch) = iter.next() {
-            taken.push(ch);
+/// Decompressed length of the text; markers inside repeated data are expanded when `recursive`.
+///
+/// Nested markers are tracked on a stack rather than by recursion, so deeply nested input cannot
+/// exhaust the stack. Each entry is the character index where the repeated data of a marker ends
+/// and how often every character up to there is repeated, `None` when that count overflows.
+pub fn decrypt(input: &str, recursive: bool) -> Result<usize> {
+    let marker = delimited(literal("("), marker_contents(), literal(")"));
+    let total = input.chars().count();
+
+    let mut result = 0usize;
+    let mut nested: Vec<(usize, Option<usize>)> = Vec::new();
+    let mut position = 0;
+    let mut rest = Input::new(input);
+    while !rest.is_empty() {
+        while nested.last().is_some_and(|(end, _)| *end <= position) {
+            nested.pop();
+        }
+        let (end, repeat) = nested.last().copied().unwrap_or((total, Some(1)));
+
+        if rest.rest().starts_with('(') {
+            let before = rest.rest().len();
+            let (length, times) = marker.parse(&mut rest)?;
+            // markers only hold ASCII, so their bytes are characters
+            position += before - rest.rest().len();
+            let (line, column) = rest.position();
+            trace!(line, column, length, times, "marker");
+
+            let left = end.saturating_sub(position);
+            if left < length {
+                let message = format!("marker repeats {} characters, only {} left", length, left);
+                return Err(Error::Parse { line, column, message });
+            }
+
+            if recursive {
+                nested.push((position + length, repeat.and_then(|repeat| repeat.checked_mul(times))));
+            } else {
+                rest.take(length);
+                position += length;
+                result = length.checked_mul(times).and_then(|length| result.checked_add(length)).ok_or_else(overflow)?;
+            }
+        } else {
+            rest.take(1);
+            position += 1;
+            result = repeat.and_then(|repeat| result.checked_add(repeat)).ok_or_else(overflow)?;
         }
-        length -= 1;
     }
 
-    taken
+    Ok(result)
 }
 
-fn find_matching_close(iter: &mut Chars) -> String {
-    let mut result: Vec<char> = Vec::new();
-    while let Some(ch) = iter.next() {
-        if ch == ')' {
-            return result.iter().collect::<String>();
-        }
+/// The compressed file is the whole input.
+pub fn parse(input: &str) -> Result<String> {
+    Ok(input.to_string())
+}
 
-        result.push(ch);
-    }
+/// Decompressed length using format version 1.
+pub fn part1(input: &str) -> Result<usize> {
+    decrypt(input, false)
+}
 
-    result.iter().collect::<String>()
+/// Decompressed length using format version 2.
+pub fn part2(input: &str) -> Result<usize> {
+    decrypt(input, true)
 }
 
-fn decrypt(input: &String, recursive: bool) -> usize {
-    let mut result = 0usize;
-    let iter = &mut input.chars();
-    while let Some(ch) = iter.next() {
-        if ch == '(' {
-            let (length, times) = repetition(find_matching_close(iter)).unwrap();
-            let taken = take(iter, length);
-            result += (if recursive { decrypt(&taken, true) } else { length }) * times;
-        } else {
-            result += 1;
-        }
-    }
+/// The [`Solution`] of day 9.
+pub struct Day9;
 
-    result
-}
+impl Solution for Day9 {
+    type Input = String;
 
-pub fn part1(input: &String) -> Result<usize, &str> {
-    Ok(decrypt(input, false))
-}
+    fn parse(&self, input: &str) -> Result<String> {
+        parse(input)
+    }
 
-pub fn part2(input: &String) -> Result<usize, &str> {
-    Ok(decrypt(input, true))
+    fn part1(&self, input: &String) -> Result<Answer> {
+        part1(input).map(Answer::from)
+    }
+
+    fn part2(&self, input: &String) -> Result<Answer> {
+        part2(input).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use proptest::prelude::*;
     use rstest::rstest;
     use crate::day9::{repetition, decrypt};
+    use crate::error::Error;
 
     #[rstest]
     fn test_command() {
@@ -86,7 +125,7 @@ mod tests {
     #[case("(6x1)(1x3)A", "(1x3)A")]
     #[case("X(8x2)(3x3)ABCY", "X(3x3)ABC(3x3)ABCY")]
     fn test_decrypt(#[case] input: &str, #[case] expected_output: &str) {
-        assert_eq!(decrypt(&input.to_string(), false), expected_output.len());
+        assert_eq!(decrypt(input, false).unwrap(), expected_output.len());
     }
 
     #[rstest]
@@ -96,6 +135,56 @@ mod tests {
     #[case("(27x12)(20x12)(13x14)(7x10)(1x12)A", 241920)]
     #[case("(25x3)(3x3)ABC(2x3)XY(5x2)PQRSTX(18x9)(3x2)TWO(5x7)SEVEN", 445)]
     fn test_decrypt2(#[case] input: &str, #[case] expected_length: usize) {
-        assert_eq!(decrypt(&input.to_string(), true), expected_length);
+        assert_eq!(decrypt(input, true).unwrap(), expected_length);
+    }
+
+    #[rstest]
+    #[case("AB(3y2)CDE", false, 1, 5)]
+    #[case("(9x1)AB(1z2)CD", true, 1, 10)]
+    #[case("AB(3x2", false, 1, 7)]
+    #[case("AB(3x2)C", false, 1, 8)]
+    #[case("(8x2)(3x3)AB", true, 1, 6)]
+    #[case("(7x2)(3x3)AB", true, 1, 11)]
+    fn test_decrypt_error(#[case] input: &str, #[case] recursive: bool, #[case] line: usize, #[case] column: usize) {
+        assert!(matches!(decrypt(input, recursive), Err(Error::Parse { line: l, column: c, .. }) if l == line && c == column));
+    }
+
+    #[rstest]
+    fn test_decrypt_deeply_nested() {
+        let mut markers = Vec::new();
+        let mut length = 1;
+        for _ in 0..20_000 {
+            let marker = format!("({}x1)", length);
+            length += marker.len();
+            markers.push(marker);
+        }
+        markers.reverse();
+        let input = markers.concat() + "A";
+
+        assert_eq!(decrypt(&input, true).unwrap(), 1);
+        assert_eq!(decrypt(&input, false).unwrap(), input.len() - markers[0].len());
+        assert!(matches!(decrypt(&input[..input.len() - 1], true), Err(Error::Parse { .. })));
+    }
+
+    #[rstest]
+    #[case("(6x99999999999)(0x99999999999)", 0)]
+    #[case("(5x2)(0x3)A", 1)]
+    fn test_decrypt_empty_markers(#[case] input: &str, #[case] expected: usize) {
+        assert_eq!(decrypt(input, true).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("(1x99999999999999999999)A", false)]
+    #[case("(11x4294967296)(1x4294967296)A", true)]
+    fn test_decrypt_overflow(#[case] input: &str, #[case] recursive: bool) {
+        assert!(decrypt(input, recursive).is_err());
+    }
+
+    proptest! {
+        #[test]
+        fn prop_text_without_markers_keeps_its_length(text in "[A-Z0-9x)]{0,200}") {
+            prop_assert_eq!(decrypt(&text, false).unwrap(), text.len());
+            prop_assert_eq!(decrypt(&text, true).unwrap(), text.len());
+        }
     }
 }
@@ -0,0 +1,115 @@
+//! The error type shared by all parsers and parts.
+
+use std::fmt;
+use crate::ioc::InputError;
+
+/// Why a day could not be parsed or solved.
+#[derive(Debug)]
+pub enum Error {
+    /// The input does not match what the day expects.
+    Parse {
+        /// The (1-based) line of the input.
+        line: usize,
+        /// The (1-based) column, in characters.
+        column: usize,
+        /// What was expected.
+        message: String,
+    },
+    /// The input parsed, but describes something that cannot be solved.
+    InvalidState(String),
+    /// Something the answer depends on is not there.
+    NotFound(String),
+    /// The input could not be loaded.
+    Input(InputError),
+    /// The part was cancelled or ran out of time, with what it found until then.
+    TimedOut {
+        /// The answer as far as it was found.
+        partial: Option<String>,
+    },
+    /// The part is still the placeholder generated by `new-day`.
+    NotImplemented,
+}
+
+/// A result failing with [`Error`].
+pub type Result<T> = std::result::Result<T, Error>;
+
+impl Error {
+    /// A parse error at the given (1-based) column of the first line of the parsed text.
+    pub fn parse(column: usize, message: impl Into<String>) -> Error {
+        Error::Parse { line: 1, column, message: message.into() }
+    }
+
+    /// Moves a parse error found in a fragment of the input to where that fragment starts in the full input.
+    pub fn at(self, line: usize, column: usize) -> Error {
+        match self {
+            Error::Parse { line: l, column: c, message } => Error::Parse {
+                line: line + l - 1,
+                column: if l == 1 { column + c - 1 } else { c },
+                message,
+            },
+            other => other,
+        }
+    }
+
+    /// A parse error for a TOML document, positioned at the line and column the TOML error points at.
+    pub fn toml(text: &str, e: &toml::de::Error) -> Error {
+        let offset = e.span().map_or(0, |span| span.start.min(text.len()));
+        let before = &text[..offset];
+        let line = before.matches('\n').count() + 1;
+        let column = before.len() - before.rfind('\n').map_or(0, |idx| idx + 1) + 1;
+        Error::Parse { line, column, message: e.message().to_string() }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse { line, column, message } => write!(f, "parse error at line {}, column {}: {}", line, column, message),
+            Error::InvalidState(message) => write!(f, "invalid state: {}", message),
+            Error::NotFound(message) => write!(f, "not found: {}", message),
+            Error::Input(e) => write!(f, "{}", e),
+            Error::TimedOut { partial: Some(partial) } if !partial.is_empty() => write!(f, "timed out, partial answer {}", partial),
+            Error::TimedOut { .. } => write!(f, "timed out"),
+            Error::NotImplemented => write!(f, "not yet implemented"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Input(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<InputError> for Error {
+    fn from(e: InputError) -> Self {
+        Error::Input(e)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use crate::error::Error;
+
+    #[rstest]
+    #[case(Error::parse(3, "x"), 1, 1, (1, 3))]
+    #[case(Error::parse(3, "x"), 5, 1, (5, 3))]
+    #[case(Error::parse(3, "x"), 1, 10, (1, 12))]
+    #[case(Error::Parse { line: 2, column: 3, message: "x".to_string() }, 4, 10, (5, 3))]
+    fn test_at(#[case] error: Error, #[case] line: usize, #[case] column: usize, #[case] expected: (usize, usize)) {
+        match error.at(line, column) {
+            Error::Parse { line, column, .. } => assert_eq!((line, column), expected),
+            other => panic!("unexpected error {:?}", other),
+        }
+    }
+
+    #[rstest]
+    fn test_display() {
+        let error = Error::parse(7, "expected digit").at(2, 1);
+        assert_eq!(error.to_string(), "parse error at line 2, column 7: expected digit");
+    }
+}
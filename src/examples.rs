@@ -0,0 +1,240 @@
+//! Puzzle examples stored as files, `res/examples/dayN/NAME.txt`, each run through its day and
+//! checked against the answers in its header or in a sidecar `NAME.toml`.
+//!
+//! A header is TOML between two `---` lines at the top of the file:
+//!
+//! ```text
+//! ---
+//! part1 = 6
+//! params = { width = 7, height = 3 }
+//! ---
+//! rect 3x2
+//! ```
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use serde::Deserialize;
+use toml::{Table, Value};
+use crate::answer::Answer;
+use crate::answers::to_answer;
+use crate::config::Params;
+use crate::error::{Error, Result};
+use crate::ioc::{line_from_file, Normalize};
+use crate::registry::{Day, DAYS};
+use crate::runner::{run_day, RunOptions};
+use crate::verify::{check, Check, diff, Status, summary};
+
+/// The directory searched when no other is given.
+pub const DIR: &str = "./res/examples";
+
+const HEADER: &str = "---";
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Header {
+    part1: Option<Value>,
+    part2: Option<Value>,
+    #[serde(default)]
+    params: Table,
+}
+
+/// An example input of a day with the answers it should give.
+pub struct Example {
+    /// The day the example is for.
+    pub day: &'static Day,
+    /// The name of the file, without extension.
+    pub name: String,
+    /// The input, without its header.
+    pub input: String,
+    /// The parameters to parse the input with.
+    pub params: Params,
+    /// The answers of part 1 and part 2, when given.
+    pub expected: [Option<Answer>; 2],
+}
+
+/// Splits a file into its header, if it starts with one, and the input after it.
+fn split_header(text: &str) -> Result<(Option<&str>, &str)> {
+    let Some(rest) = text.strip_prefix(HEADER).and_then(|rest| rest.strip_prefix('\n').or(rest.strip_prefix("\r\n"))) else {
+        return Ok((None, text));
+    };
+
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == HEADER {
+            return Ok((Some(&rest[..offset]), &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+
+    Err(Error::parse(1, "header is not closed by a '---' line"))
+}
+
+fn header(text: &str) -> Result<Header> {
+    // The header starts on the second line of the file.
+    toml::from_str(text).map_err(|e| Error::toml(text, &e).at(2, 1))
+}
+
+impl Example {
+    /// Reads an example of `day`; the answers come from its header or from the sidecar next to it.
+    pub fn load(day: &'static Day, path: &Path) -> Result<Example> {
+        let text = line_from_file(path, &Normalize::NONE)?;
+        let (inline, input) = split_header(&text)?;
+
+        let sidecar = path.with_extension("toml");
+        let header = match (inline, sidecar.exists()) {
+            (Some(_), true) => return Err(Error::InvalidState(format!("{} has both a header and a sidecar {}", path.display(), sidecar.display()))),
+            (Some(inline), false) => header(inline)?,
+            (None, true) => header(&line_from_file(&sidecar, &Normalize::NONE)?)?,
+            (None, false) => Header::default(),
+        };
+
+        let [part1, part2] = [&header.part1, &header.part2].map(|value| value.as_ref().map(to_answer).transpose());
+        Ok(Example {
+            day,
+            name: path.file_stem().map_or_else(String::new, |name| name.to_string_lossy().into_owned()),
+            input: day.puzzle.normalize().apply(input.to_string()),
+            params: Params::from(header.params),
+            expected: [part1?, part2?],
+        })
+    }
+
+    /// The expected answer of part 1 or 2.
+    pub fn expected(&self, part: u8) -> Option<&Answer> {
+        self.expected.get(usize::from(part).checked_sub(1)?)?.as_ref()
+    }
+
+    /// Runs the example once and checks every part that has an expected answer; the other part may
+    /// well fail on an input that was only meant for one of them.
+    pub fn check(&self) -> Vec<Check> {
+        let result = run_day(self.day, &self.input, &self.params, &RunOptions::default());
+        check(&result, |part| self.expected(part).cloned()).into_iter()
+            .filter(|check| check.part == 0 || check.expected.is_some())
+            .collect()
+    }
+}
+
+fn files(dir: &Path) -> Result<Vec<PathBuf>> {
+    let unreadable = |e: std::io::Error| Error::InvalidState(format!("unable to list {}: {}", dir.display(), e));
+
+    let mut files = Vec::new();
+    for entry in fs::read_dir(dir).map_err(unreadable)? {
+        let path = entry.map_err(unreadable)?.path();
+        if path.is_file() && path.extension().is_some_and(|ext| ext == "txt") {
+            files.push(path);
+        }
+    }
+
+    files.sort();
+    Ok(files)
+}
+
+/// The examples of the registered days selected by `select`, found in `dir/dayN/*.txt`, by day and name.
+pub fn discover(dir: impl AsRef<Path>, select: impl Fn(u8) -> bool) -> Result<Vec<Example>> {
+    let mut examples = Vec::new();
+    for day in DAYS.iter().filter(|day| select(day.number)) {
+        let dir = dir.as_ref().join(format!("day{}", day.number));
+        if !dir.is_dir() {
+            continue;
+        }
+
+        for path in files(&dir)? {
+            examples.push(Example::load(day, &path).map_err(|e| match e {
+                Error::Parse { line, column, message } => Error::InvalidState(format!("{}:{}:{}: {}", path.display(), line, column, message)),
+                other => other,
+            })?);
+        }
+    }
+
+    Ok(examples)
+}
+
+/// Renders one line per example with the status of every part, followed by the details of every failure.
+pub fn table(checked: &[(&Example, Vec<Check>)]) -> String {
+    let mut table = String::new();
+    for (example, checks) in checked {
+        let statuses: Vec<String> = checks.iter()
+            .map(|check| if check.part == 0 { check.status.to_string() } else { format!("part {} {}", check.part, check.status) })
+            .collect();
+        writeln!(table, "day {:>2} {:<20} {}", example.day.number, example.name, statuses.join(", ")).unwrap();
+    }
+
+    for (example, check) in checked.iter().flat_map(|(example, checks)| checks.iter().map(move |check| (example, check))) {
+        if !check.failed() {
+            continue;
+        }
+
+        writeln!(table).unwrap();
+        match (&check.status, &check.expected) {
+            (Status::Fail, Some(expected)) => {
+                writeln!(table, "day {} {} part {}:", check.day, example.name, check.part).unwrap();
+                for line in diff(&expected.to_string(), &check.actual) {
+                    writeln!(table, "  {}", line).unwrap();
+                }
+            },
+            _ => writeln!(table, "day {} {}: {}", check.day, example.name, summary(&check.actual)).unwrap(),
+        }
+    }
+
+    let failed = checked.iter().filter(|(_, checks)| checks.iter().any(Check::failed)).count();
+    writeln!(table).unwrap();
+    writeln!(table, "{} examples, {} failed", checked.len(), failed).unwrap();
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use rstest::rstest;
+    use crate::answer::Answer;
+    use crate::error::Error;
+    use crate::examples::{Check, discover, DIR, Example, split_header, table};
+    use crate::registry::DAYS;
+    use crate::testing::TempDir;
+
+    #[rstest]
+    #[case("R2, L3", None, "R2, L3")]
+    #[case("---\npart1 = 5\n---\nR2, L3\n", Some("part1 = 5\n"), "R2, L3\n")]
+    #[case("---\r\n---\r\nR2", Some(""), "R2")]
+    fn test_split_header(#[case] text: &str, #[case] header: Option<&str>, #[case] input: &str) {
+        assert_eq!(split_header(text).unwrap(), (header, input));
+    }
+
+    #[rstest]
+    fn test_split_header_unclosed() {
+        assert!(matches!(split_header("---\npart1 = 5\nR2"), Err(Error::Parse { .. })));
+    }
+
+    #[rstest]
+    fn test_load() {
+        let dir = TempDir::new("examples");
+        fs::write(dir.join("header.txt"), "---\npart1 = 5\n---\nR2, L3\n").unwrap();
+        fs::write(dir.join("sidecar.txt"), "R8, R4, R4, R8").unwrap();
+        fs::write(dir.join("sidecar.toml"), "part2 = '4'").unwrap();
+        fs::write(dir.join("both.txt"), "---\npart1 = 5\n---\nR2, L3\n").unwrap();
+        fs::write(dir.join("both.toml"), "part1 = 5").unwrap();
+        fs::write(dir.join("bad.txt"), "---\npart3 = 5\n---\nR2, L3\n").unwrap();
+
+        let header = Example::load(&DAYS[0], &dir.join("header.txt"));
+        let sidecar = Example::load(&DAYS[0], &dir.join("sidecar.txt"));
+        let both = Example::load(&DAYS[0], &dir.join("both.txt"));
+        let bad = Example::load(&DAYS[0], &dir.join("bad.txt"));
+
+        let header = header.unwrap();
+        assert_eq!((header.name.as_str(), header.input.as_str()), ("header", "R2, L3"));
+        assert_eq!(header.expected, [Some(Answer::Integer(5)), None]);
+        assert_eq!(sidecar.unwrap().expected, [None, Some(Answer::Integer(4))]);
+        assert!(matches!(both, Err(Error::InvalidState(_))));
+        assert!(matches!(bad, Err(Error::Parse { line: 2, .. })));
+    }
+
+    #[rstest]
+    fn test_examples() {
+        let examples = discover(DIR, |_| true).unwrap();
+        let checked: Vec<(&Example, Vec<Check>)> = examples.iter().map(|example| (example, example.check())).collect();
+
+        let failed = checked.iter().any(|(_, checks)| checks.iter().any(Check::failed));
+        assert!(!failed, "{}", table(&checked));
+    }
+}
@@ -0,0 +1,379 @@
+//! Seeded random inputs of a configurable size for the days, to test and benchmark the solutions
+//! on more than the one input of each account.
+
+use std::fmt::Write;
+use crate::day4::checksum;
+use crate::error::{Error, Result};
+
+/// A small SplitMix64 generator, so a seed gives the same input on every platform and release.
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    /// A generator starting from `seed`.
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    /// The next number of the sequence.
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `low..=high`.
+    pub fn between(&mut self, low: usize, high: usize) -> usize {
+        low + (self.next_u64() % (high - low + 1) as u64) as usize
+    }
+
+    /// True `percent` out of a hundred times.
+    pub fn chance(&mut self, percent: usize) -> bool {
+        self.between(1, 100) <= percent
+    }
+
+    /// One of the items, which must not be empty.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.between(0, items.len() - 1)]
+    }
+
+    /// Puts the items in a random order.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.between(0, i));
+        }
+    }
+
+    /// `length` random letters counting from `first`, `b'a'` or `b'A'`.
+    pub fn letters(&mut self, first: u8, length: usize) -> String {
+        (0..length).map(|_| (first + self.between(0, 25) as u8) as char).collect()
+    }
+}
+
+/// The generator of a day and what its size counts.
+pub struct Generator {
+    /// The number of the day.
+    pub day: u8,
+    /// The size of a real input.
+    pub size: usize,
+    /// What the size counts, like `rooms`.
+    pub unit: &'static str,
+    generate: fn(&mut Rng, usize) -> Result<String>,
+}
+
+/// The generator of every day, in order.
+pub static GENERATORS: &[Generator] = &[
+    Generator { day: 1, size: 160, unit: "instructions", generate: walk },
+    Generator { day: 2, size: 5, unit: "lines", generate: keypad },
+    Generator { day: 3, size: 1800, unit: "rows", generate: triangles },
+    Generator { day: 4, size: 1000, unit: "rooms", generate: rooms },
+    Generator { day: 5, size: 8, unit: "letters", generate: door },
+    Generator { day: 6, size: 600, unit: "messages", generate: messages },
+    Generator { day: 7, size: 2000, unit: "addresses", generate: addresses },
+    Generator { day: 8, size: 160, unit: "commands", generate: screen },
+    Generator { day: 9, size: 15000, unit: "characters", generate: compressed },
+    Generator { day: 10, size: 200, unit: "bots", generate: bots },
+];
+
+/// A random input for `day` of `size` units, its default size if not given.
+pub fn generate(day: u8, size: Option<usize>, seed: u64) -> Result<String> {
+    let generator = GENERATORS.iter().find(|generator| generator.day == day)
+        .ok_or_else(|| Error::NotFound(format!("input generator for day {}", day)))?;
+    let size = size.unwrap_or(generator.size);
+    if size == 0 {
+        return Err(Error::InvalidState(format!("an input of day {} needs at least one of its {}", day, generator.unit)));
+    }
+
+    (generator.generate)(&mut Rng::new(seed), size)
+}
+
+/// Turns and distances, like `R4, L12`.
+fn walk(rng: &mut Rng, size: usize) -> Result<String> {
+    let instructions: Vec<String> = (0..size)
+        .map(|_| format!("{}{}", rng.pick(&['L', 'R']), rng.between(1, 200)))
+        .collect();
+    Ok(instructions.join(", "))
+}
+
+/// Lines of `U`, `D`, `L` and `R` moves, one line per button.
+fn keypad(rng: &mut Rng, size: usize) -> Result<String> {
+    let lines: Vec<String> = (0..size)
+        .map(|_| {
+            let length = rng.between(50, 500);
+            (0..length).map(|_| *rng.pick(&['U', 'D', 'L', 'R'])).collect()
+        })
+        .collect();
+    Ok(lines.join("\n"))
+}
+
+/// Right aligned sides, rounded up to a multiple of three rows so they can be read by column.
+fn triangles(rng: &mut Rng, size: usize) -> Result<String> {
+    let mut text = String::new();
+    for _ in 0..size.div_ceil(3) * 3 {
+        writeln!(text, "{:>5}{:>5}{:>5}", rng.between(1, 999), rng.between(1, 999), rng.between(1, 999)).unwrap();
+    }
+    Ok(text)
+}
+
+/// The name shifted back by the sector id, the reverse of [`crate::day4::Room::decrypt`].
+fn encrypt(name: &str, sector_id: usize) -> String {
+    name.chars()
+        .map(|ch| match ch {
+            ' ' => '-',
+            ch => (b'a' + ((ch as u8 - b'a') as usize + 26 - sector_id % 26) as u8 % 26) as char,
+        })
+        .collect()
+}
+
+/// Rooms of which about half are decoys with a wrong checksum, and one real room holding the
+/// North Pole objects.
+fn rooms(rng: &mut Rng, size: usize) -> Result<String> {
+    let storage = rng.between(0, size - 1);
+    let mut text = String::new();
+    for idx in 0..size {
+        let sector_id = rng.between(100, 999);
+        let name = if idx == storage {
+            encrypt("northpole object storage", sector_id)
+        } else {
+            let words: Vec<String> = (0..rng.between(1, 5)).map(|_| {
+                let length = rng.between(2, 10);
+                rng.letters(b'a', length)
+            }).collect();
+            words.join("-")
+        };
+
+        let real = checksum(&name);
+        let mut room_checksum = real.clone();
+        while idx != storage && room_checksum == real && rng.chance(50) {
+            room_checksum = rng.letters(b'a', 5);
+        }
+        writeln!(text, "{}-{}[{}]", name, sector_id, room_checksum).unwrap();
+    }
+    Ok(text)
+}
+
+/// A door id of lowercase letters.
+fn door(rng: &mut Rng, size: usize) -> Result<String> {
+    Ok(rng.letters(b'a', size))
+}
+
+/// Messages of eight letters; on every position one letter is the most and one the least common,
+/// so both parts have a single answer.
+fn messages(rng: &mut Rng, size: usize) -> Result<String> {
+    if size < 3 {
+        return Err(Error::InvalidState("the messages need at least 3 lines to have a most and a least common letter".to_string()));
+    }
+
+    let mut columns = Vec::new();
+    for _ in 0..8 {
+        let mut alphabet: Vec<char> = ('a'..='z').collect();
+        rng.shuffle(&mut alphabet);
+
+        // The least common letter once, a few others `count` times each and the most common in the rest.
+        let rest = size - 1;
+        let count = rng.between(2, 4);
+        let others = (rest.saturating_sub(count + 1) / count).min(24);
+        let mut column = vec![alphabet[0]];
+        for letter in &alphabet[1..=others] {
+            column.extend(std::iter::repeat_n(*letter, count));
+        }
+        column.extend(std::iter::repeat_n(alphabet[25], rest - others * count));
+
+        rng.shuffle(&mut column);
+        columns.push(column);
+    }
+
+    let mut text = String::new();
+    for row in 0..size {
+        let message: String = columns.iter().map(|column| column[row]).collect();
+        writeln!(text, "{}", message).unwrap();
+    }
+    Ok(text)
+}
+
+/// Addresses with one to three hypernet sequences, some with an ABBA or an ABA and its BAB.
+fn addresses(rng: &mut Rng, size: usize) -> Result<String> {
+    let mut text = String::new();
+    for _ in 0..size {
+        let mut parts: Vec<String> = (0..rng.between(1, 3) * 2 + 1).map(|_| {
+            let length = rng.between(4, 12);
+            rng.letters(b'a', length)
+        }).collect();
+
+        let [a, b] = [rng.letters(b'a', 1), rng.letters(b'a', 1)];
+        if a != b && rng.chance(30) {
+            let part = rng.between(0, parts.len() - 1);
+            parts[part].push_str(&format!("{}{}{}{}", a, b, b, a));
+        }
+        if a != b && rng.chance(30) {
+            let (supernet, hypernet) = (rng.between(0, parts.len() / 2) * 2, rng.between(0, parts.len() / 2 - 1) * 2 + 1);
+            parts[supernet].push_str(&format!("{}{}{}", a, b, a));
+            parts[hypernet].push_str(&format!("{}{}{}", b, a, b));
+        }
+
+        for (idx, part) in parts.iter().enumerate() {
+            match idx % 2 {
+                0 => text.push_str(part),
+                _ => write!(text, "[{}]", part).unwrap(),
+            }
+        }
+        text.push('\n');
+    }
+    Ok(text)
+}
+
+/// Rects and rotations that stay on the default 50 by 6 screen.
+fn screen(rng: &mut Rng, size: usize) -> Result<String> {
+    let mut text = String::new();
+    for _ in 0..size {
+        match rng.between(0, 2) {
+            0 => writeln!(text, "rect {}x{}", rng.between(1, 10), rng.between(1, 3)),
+            1 => writeln!(text, "rotate row y={} by {}", rng.between(0, 5), rng.between(1, 49)),
+            _ => writeln!(text, "rotate column x={} by {}", rng.between(0, 49), rng.between(1, 5)),
+        }.unwrap();
+    }
+    Ok(text)
+}
+
+/// At least `budget` characters of letters and markers, the markers nested at most `depth` deep.
+fn chunk(rng: &mut Rng, budget: usize, depth: usize) -> String {
+    let mut text = String::new();
+    while text.len() < budget {
+        if depth > 0 && rng.chance(30) {
+            let length = rng.between(1, 40);
+            let repeated = chunk(rng, length, depth - 1);
+            write!(text, "({}x{}){}", repeated.len(), rng.between(1, 10), repeated).unwrap();
+        } else {
+            let length = rng.between(1, 10);
+            text.push_str(&rng.letters(b'A', length));
+        }
+    }
+    text
+}
+
+/// A compressed file whose markers only repeat whole markers, so both format versions can read it.
+fn compressed(rng: &mut Rng, size: usize) -> Result<String> {
+    Ok(chunk(rng, size, 3))
+}
+
+#[derive(Clone, Copy)]
+enum Target {
+    Bot(usize),
+    Output(usize),
+}
+
+/// Bots that only hand chips to bots further down the network, so every bot gets exactly two chips
+/// and there are no cycles. The first bot compares the value-17 and value-61 chips and puts them
+/// in outputs; bot numbers, outputs and the order of the lines are shuffled.
+fn bots(rng: &mut Rng, size: usize) -> Result<String> {
+    if !(2..=256).contains(&size) {
+        return Err(Error::InvalidState(format!("a network has 2 to 256 bots, not {}", size)));
+    }
+
+    let mut open = vec![2; size];
+    let mut outputs = 0;
+    let mut targets = Vec::new();
+    for bot in 0..size {
+        let mut target = || {
+            let later: Vec<usize> = (bot + 1..size).filter(|&other| open[other] > 0).collect();
+            if bot > 0 && !later.is_empty() && rng.chance(70) {
+                let other = *rng.pick(&later);
+                open[other] -= 1;
+                Target::Bot(other)
+            } else {
+                outputs += 1;
+                Target::Output(outputs - 1)
+            }
+        };
+        targets.push([target(), target()]);
+    }
+
+    let needed: usize = open.iter().sum();
+    if needed > 255 || outputs > 256 {
+        return Err(Error::InvalidState(format!("a network of {} bots needs {} chips and {} outputs, more than fit in a byte", size, needed, outputs)));
+    }
+
+    let mut values: Vec<usize> = (1..=255).filter(|value| *value != 17 && *value != 61).collect();
+    rng.shuffle(&mut values);
+    let mut values = [17, 61].into_iter().chain(values);
+    let mut ids: Vec<usize> = (0..=255).collect();
+    rng.shuffle(&mut ids);
+    let mut output_ids: Vec<usize> = (0..outputs).collect();
+    rng.shuffle(&mut output_ids);
+
+    let name = |target: Target| match target {
+        Target::Bot(bot) => format!("bot {}", ids[bot]),
+        Target::Output(output) => format!("output {}", output_ids[output]),
+    };
+    let mut lines = Vec::new();
+    for (bot, [low, high]) in targets.into_iter().enumerate() {
+        lines.push(format!("bot {} gives low to {} and high to {}", ids[bot], name(low), name(high)));
+        for _ in 0..open[bot] {
+            lines.push(format!("value {} goes to bot {}", values.next().unwrap(), ids[bot]));
+        }
+    }
+
+    rng.shuffle(&mut lines);
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use crate::config::Params;
+    use crate::day5;
+    use crate::error::Error;
+    use crate::generate::{generate, GENERATORS};
+    use crate::registry::DAYS;
+    use crate::runner::{run_day, RunOptions};
+
+    #[rstest]
+    fn test_seeded() {
+        for generator in GENERATORS {
+            assert_eq!(generate(generator.day, None, 7).unwrap(), generate(generator.day, None, 7).unwrap());
+            assert_ne!(generate(generator.day, None, 7).unwrap(), generate(generator.day, None, 8).unwrap());
+        }
+    }
+
+    #[rstest]
+    #[case(1)]
+    #[case(2)]
+    #[case(3)]
+    #[case(4)]
+    #[case(6)]
+    #[case(7)]
+    #[case(8)]
+    #[case(9)]
+    #[case(10)]
+    fn test_solvable(#[case] day: u8) {
+        let day = DAYS.iter().find(|d| d.number == day).unwrap();
+        for seed in 0..5 {
+            let input = generate(day.number, None, seed).unwrap();
+            let result = run_day(day, &input, &Params::default(), &RunOptions::default());
+            assert!(result.success(), "day {} seed {}: {:?} {:?}", day.number, seed, result.parse_error,
+                result.parts.iter().map(|part| &part.answer).collect::<Vec<_>>());
+        }
+    }
+
+    #[rstest]
+    fn test_door() {
+        assert!(day5::parse(&generate(5, None, 1).unwrap()).is_ok());
+    }
+
+    #[rstest]
+    #[case(1, 0)]
+    #[case(6, 2)]
+    #[case(10, 1)]
+    #[case(10, 257)]
+    fn test_invalid_size(#[case] day: u8, #[case] size: usize) {
+        assert!(matches!(generate(day, Some(size), 1), Err(Error::InvalidState(_))));
+    }
+
+    #[rstest]
+    fn test_unknown_day() {
+        assert!(matches!(generate(11, None, 1), Err(Error::NotFound(_))));
+    }
+}
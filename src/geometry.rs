@@ -0,0 +1,486 @@
+//! Points, compass headings and grids on a 2D plane: bounded grids for keypads and screens and
+//! unbounded grids for walks without edges. `y` grows downwards, row 0 is the top row.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Add;
+use std::str::FromStr;
+use serde::Deserialize;
+use crate::error::{Error, Result};
+
+/// A position, with coordinates of type `T`: `usize` for cells of a bounded grid, `i32` for an
+/// unbounded plane.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point<T> {
+    /// The column, growing to the right.
+    pub x: T,
+    /// The row, growing downwards.
+    pub y: T,
+}
+
+impl<T> Point<T> {
+    /// The point at `x` and `y`.
+    pub const fn new(x: T, y: T) -> Point<T> {
+        Point { x, y }
+    }
+}
+
+impl<T: Default> Point<T> {
+    /// The point at `(0, 0)`.
+    pub fn origin() -> Point<T> {
+        Point::default()
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Point<T>;
+
+    fn add(self, other: Point<T>) -> Point<T> {
+        Point::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Point<i32> {
+    /// The number of blocks to walk from the origin, moving only horizontally and vertically;
+    /// `None` when that does not fit an `i32`.
+    pub fn manhattan_distance(&self) -> Option<i32> {
+        self.x.checked_abs()?.checked_add(self.y.checked_abs()?)
+    }
+}
+
+/// One of the eight compass points, in clockwise order. North is up, towards row 0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(try_from = "String")]
+pub enum Heading {
+    /// Up.
+    North,
+    /// Up and to the right.
+    NorthEast,
+    /// To the right.
+    East,
+    /// Down and to the right.
+    SouthEast,
+    /// Down.
+    South,
+    /// Down and to the left.
+    SouthWest,
+    /// To the left.
+    West,
+    /// Up and to the left.
+    NorthWest,
+}
+
+impl Heading {
+    /// The four headings along the axes, clockwise from north.
+    pub const ORTHOGONAL: [Heading; 4] = [Heading::North, Heading::East, Heading::South, Heading::West];
+
+    /// All eight headings, clockwise from north.
+    pub const ALL: [Heading; 8] = [
+        Heading::North, Heading::NorthEast, Heading::East, Heading::SouthEast,
+        Heading::South, Heading::SouthWest, Heading::West, Heading::NorthWest,
+    ];
+
+    /// The heading after turning `eighths` of a full circle clockwise; negative turns counter-clockwise.
+    pub fn turn(self, eighths: i32) -> Heading {
+        Heading::ALL[(self as i32 + eighths).rem_euclid(8) as usize]
+    }
+
+    /// A quarter turn to the left.
+    pub fn left(self) -> Heading {
+        self.turn(-2)
+    }
+
+    /// A quarter turn to the right.
+    pub fn right(self) -> Heading {
+        self.turn(2)
+    }
+
+    /// A half turn.
+    pub fn reverse(self) -> Heading {
+        self.turn(4)
+    }
+
+    /// Whether the heading is along an axis, not diagonal.
+    pub fn is_orthogonal(self) -> bool {
+        (self as usize).is_multiple_of(2)
+    }
+
+    /// The step of one cell in this heading, as `(dx, dy)`.
+    pub fn offset(self) -> (isize, isize) {
+        match self {
+            Heading::North => (0, -1),
+            Heading::NorthEast => (1, -1),
+            Heading::East => (1, 0),
+            Heading::SouthEast => (1, 1),
+            Heading::South => (0, 1),
+            Heading::SouthWest => (-1, 1),
+            Heading::West => (-1, 0),
+            Heading::NorthWest => (-1, -1),
+        }
+    }
+
+    /// The step of one block in this heading on an unbounded plane.
+    pub fn unit(self) -> Point<i32> {
+        let (dx, dy) = self.offset();
+        Point::new(dx as i32, dy as i32)
+    }
+
+    /// The heading for a compass letter: `N`, `E`, `S` or `W`.
+    pub fn from_compass(letter: char) -> Option<Heading> {
+        match letter {
+            'N' => Some(Heading::North),
+            'E' => Some(Heading::East),
+            'S' => Some(Heading::South),
+            'W' => Some(Heading::West),
+            _ => None,
+        }
+    }
+
+    /// The heading for a move on a screen or keypad: `U`, `R`, `D` or `L`.
+    pub fn from_arrow(letter: char) -> Option<Heading> {
+        match letter {
+            'U' => Some(Heading::North),
+            'R' => Some(Heading::East),
+            'D' => Some(Heading::South),
+            'L' => Some(Heading::West),
+            _ => None,
+        }
+    }
+}
+
+/// Parses an abbreviation like `N` or `SW`, or a name like `north` or `south-west`, ignoring case.
+impl FromStr for Heading {
+    type Err = Error;
+
+    fn from_str(text: &str) -> Result<Heading> {
+        let name: String = text.chars().filter(|c| *c != '-' && *c != '_').collect::<String>().to_uppercase();
+        let heading = match name.as_str() {
+            "N" | "NORTH" => Heading::North,
+            "NE" | "NORTHEAST" => Heading::NorthEast,
+            "E" | "EAST" => Heading::East,
+            "SE" | "SOUTHEAST" => Heading::SouthEast,
+            "S" | "SOUTH" => Heading::South,
+            "SW" | "SOUTHWEST" => Heading::SouthWest,
+            "W" | "WEST" => Heading::West,
+            "NW" | "NORTHWEST" => Heading::NorthWest,
+            _ => return Err(Error::parse(1, format!("invalid heading '{}'", text))),
+        };
+        Ok(heading)
+    }
+}
+
+impl TryFrom<String> for Heading {
+    type Error = Error;
+
+    fn try_from(text: String) -> Result<Heading> {
+        text.parse()
+    }
+}
+
+/// A rectangle of cells, `width` wide and `height` high.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    /// A grid with every cell set to `value`.
+    pub fn new(width: usize, height: usize, value: T) -> Grid<T> {
+        Grid { width, height, cells: vec![value; width * height] }
+    }
+}
+
+impl<T> Grid<T> {
+    /// A grid from its rows, which all must have the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Result<Grid<T>> {
+        let width = rows.first().map_or(0, Vec::len);
+        if let Some((y, row)) = rows.iter().enumerate().find(|(_, row)| row.len() != width) {
+            return Err(Error::InvalidState(format!("row {} has {} cells, expected {}", y, row.len(), width)));
+        }
+
+        let height = rows.len();
+        Ok(Grid { width, height, cells: rows.into_iter().flatten().collect() })
+    }
+
+    /// The number of columns.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// The number of rows.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Whether the point is on the grid.
+    pub fn contains(&self, point: Point<usize>) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    /// The cell at the point, if that is on the grid.
+    pub fn get(&self, point: Point<usize>) -> Option<&T> {
+        if self.contains(point) { self.cells.get(point.y * self.width + point.x) } else { None }
+    }
+
+    /// The cell at the point to change, if that is on the grid.
+    pub fn get_mut(&mut self, point: Point<usize>) -> Option<&mut T> {
+        if self.contains(point) { self.cells.get_mut(point.y * self.width + point.x) } else { None }
+    }
+
+    /// Sets the cell, failing for a point outside the grid.
+    pub fn set(&mut self, point: Point<usize>, value: T) -> Result<()> {
+        let (width, height) = (self.width, self.height);
+        let cell = self.get_mut(point)
+            .ok_or_else(|| Error::InvalidState(format!("point ({}, {}) is outside the {}x{} grid", point.x, point.y, width, height)))?;
+        *cell = value;
+        Ok(())
+    }
+
+    /// The neighbouring point in the heading, if that is on the grid.
+    pub fn next(&self, point: Point<usize>, heading: Heading) -> Option<Point<usize>> {
+        let (dx, dy) = heading.offset();
+        self.step(point, dx, dy)
+    }
+
+    /// The point `dx` and `dy` away from `point`, if that is on the grid.
+    pub fn step(&self, point: Point<usize>, dx: isize, dy: isize) -> Option<Point<usize>> {
+        let next = Point::new(point.x.checked_add_signed(dx)?, point.y.checked_add_signed(dy)?);
+        if self.contains(next) { Some(next) } else { None }
+    }
+
+    /// The neighbours on the grid that share an edge with `point`.
+    pub fn neighbours(&self, point: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        Heading::ORTHOGONAL.into_iter().filter_map(move |heading| self.next(point, heading))
+    }
+
+    /// The neighbours on the grid that share an edge or a corner with `point`.
+    pub fn neighbours8(&self, point: Point<usize>) -> impl Iterator<Item = Point<usize>> + '_ {
+        Heading::ALL.into_iter().filter_map(move |heading| self.next(point, heading))
+    }
+
+    /// The rows from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1)).take(self.height)
+    }
+
+    /// The number of cells matching the predicate.
+    pub fn count(&self, predicate: impl Fn(&T) -> bool) -> usize {
+        self.cells.iter().filter(|cell| predicate(cell)).count()
+    }
+
+    /// Shifts row `y` right by `by` cells, wrapping around.
+    pub fn rotate_row(&mut self, y: usize, by: usize) -> Result<()> {
+        if y >= self.height {
+            return Err(Error::InvalidState(format!("invalid row {}, must be 0..{}", y, self.height)));
+        }
+
+        if self.width > 0 {
+            self.cells[y * self.width..(y + 1) * self.width].rotate_right(by % self.width);
+        }
+        Ok(())
+    }
+
+    /// Shifts column `x` down by `by` cells, wrapping around.
+    pub fn rotate_column(&mut self, x: usize, by: usize) -> Result<()> {
+        if x >= self.width {
+            return Err(Error::InvalidState(format!("invalid column {}, must be 0..{}", x, self.width)));
+        }
+
+        for _ in 0..by % self.height.max(1) {
+            for y in (1..self.height).rev() {
+                self.cells.swap(y * self.width + x, (y - 1) * self.width + x);
+            }
+        }
+        Ok(())
+    }
+
+    /// One line per row, with a character per cell.
+    pub fn render(&self, cell: impl Fn(&T) -> char) -> String {
+        let mut text = String::with_capacity((self.width + 1) * self.height);
+        for row in self.rows() {
+            text.extend(row.iter().map(&cell));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl fmt::Display for Grid<char> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.render(|cell| *cell))
+    }
+}
+
+/// A grid without edges that only stores the cells that were set.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point<i32>, T>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid { cells: HashMap::new() }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    /// A grid without cells.
+    pub fn new() -> SparseGrid<T> {
+        SparseGrid::default()
+    }
+
+    /// Sets the cell, returning the value it had before.
+    pub fn insert(&mut self, point: Point<i32>, value: T) -> Option<T> {
+        self.cells.insert(point, value)
+    }
+
+    /// The cell at the point, if it was set.
+    pub fn get(&self, point: Point<i32>) -> Option<&T> {
+        self.cells.get(&point)
+    }
+
+    /// Whether the cell at the point was set.
+    pub fn contains(&self, point: Point<i32>) -> bool {
+        self.cells.contains_key(&point)
+    }
+
+    /// The number of cells that were set.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Whether no cell was set.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// The top-left and bottom-right corners of the cells that were set.
+    pub fn bounds(&self) -> Option<(Point<i32>, Point<i32>)> {
+        let mut points = self.cells.keys();
+        let first = *points.next()?;
+        Some(points.fold((first, first), |(min, max), p| {
+            (Point::new(min.x.min(p.x), min.y.min(p.y)), Point::new(max.x.max(p.x), max.y.max(p.y)))
+        }))
+    }
+
+    /// The cells within the bounds, one line per row; `cell` gets `None` for cells that were not set.
+    pub fn render(&self, cell: impl Fn(Option<&T>) -> char) -> String {
+        let Some((min, max)) = self.bounds() else { return String::new() };
+
+        let mut text = String::new();
+        for y in min.y..=max.y {
+            text.extend((min.x..=max.x).map(|x| cell(self.get(Point::new(x, y)))));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use crate::error::Error;
+    use crate::geometry::{Grid, Heading, Point, SparseGrid};
+
+    fn keypad() -> Grid<char> {
+        Grid::from_rows(vec![vec!['1', '2', '3'], vec!['4', '5', '6'], vec!['7', '8', '9']]).unwrap()
+    }
+
+    #[rstest]
+    fn test_point() {
+        assert_eq!(Point::new(2, -3) + Point::new(-1, 1), Point::new(1, -2));
+        assert_eq!(Point::new(2, -3).manhattan_distance(), Some(5));
+        assert_eq!(Point::new(i32::MIN, 0).manhattan_distance(), None);
+        assert_eq!(Point::new(i32::MAX, 1).manhattan_distance(), None);
+        assert_eq!(Point::<i32>::origin(), Point::new(0, 0));
+    }
+
+    #[rstest]
+    #[case(Heading::North, Heading::West, Heading::East, Heading::South)]
+    #[case(Heading::West, Heading::South, Heading::North, Heading::East)]
+    #[case(Heading::NorthEast, Heading::NorthWest, Heading::SouthEast, Heading::SouthWest)]
+    fn test_turn(#[case] heading: Heading, #[case] left: Heading, #[case] right: Heading, #[case] reverse: Heading) {
+        assert_eq!(heading.left(), left);
+        assert_eq!(heading.right(), right);
+        assert_eq!(heading.reverse(), reverse);
+        assert_eq!(heading.turn(8), heading);
+        assert_eq!(heading.turn(-1).turn(1), heading);
+    }
+
+    #[rstest]
+    fn test_heading() {
+        assert_eq!(Heading::SouthWest.unit(), Point::new(-1, 1));
+        assert_eq!(Heading::North.unit() + Heading::South.unit(), Point::origin());
+        assert!(Heading::West.is_orthogonal());
+        assert!(!Heading::NorthWest.is_orthogonal());
+        assert_eq!(Heading::from_compass('E'), Some(Heading::East));
+        assert_eq!(Heading::from_arrow('U'), Some(Heading::North));
+        assert_eq!(Heading::from_arrow('N'), None);
+    }
+
+    #[rstest]
+    #[case("N", Heading::North)]
+    #[case("sw", Heading::SouthWest)]
+    #[case("north-east", Heading::NorthEast)]
+    #[case("West", Heading::West)]
+    fn test_parse_heading(#[case] input: &str, #[case] expected: Heading) {
+        assert_eq!(input.parse::<Heading>().unwrap(), expected);
+        assert!(matches!("up".parse::<Heading>(), Err(Error::Parse { .. })));
+    }
+
+    #[rstest]
+    fn test_grid() {
+        let mut grid = keypad();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid.get(Point::new(2, 1)), Some(&'6'));
+        assert_eq!(grid.get(Point::new(3, 1)), None);
+
+        grid.set(Point::new(0, 0), '*').unwrap();
+        assert_eq!(grid.to_string(), "*23\n456\n789\n");
+        assert!(matches!(grid.set(Point::new(0, 3), '*'), Err(Error::InvalidState(_))));
+        assert!(matches!(Grid::from_rows(vec![vec![1, 2], vec![3]]), Err(Error::InvalidState(_))));
+    }
+
+    #[rstest]
+    #[case(Point::new(1, 1), 4, 8)]
+    #[case(Point::new(0, 0), 2, 3)]
+    #[case(Point::new(2, 1), 3, 5)]
+    fn test_neighbours(#[case] point: Point<usize>, #[case] orthogonal: usize, #[case] all: usize) {
+        let grid = keypad();
+        assert_eq!(grid.neighbours(point).count(), orthogonal);
+        assert_eq!(grid.neighbours8(point).count(), all);
+    }
+
+    #[rstest]
+    fn test_step() {
+        let grid = keypad();
+        assert_eq!(grid.step(Point::new(0, 0), 1, 2), Some(Point::new(1, 2)));
+        assert_eq!(grid.step(Point::new(0, 0), -1, 0), None);
+        assert_eq!(grid.step(Point::new(2, 2), 0, 1), None);
+    }
+
+    #[rstest]
+    fn test_rotate() {
+        let mut grid = keypad();
+        grid.rotate_row(0, 4).unwrap();
+        assert_eq!(grid.to_string(), "312\n456\n789\n");
+        grid.rotate_column(1, 2).unwrap();
+        assert_eq!(grid.to_string(), "352\n486\n719\n");
+        assert!(matches!(grid.rotate_row(3, 1), Err(Error::InvalidState(_))));
+        assert!(matches!(grid.rotate_column(3, 1), Err(Error::InvalidState(_))));
+    }
+
+    #[rstest]
+    fn test_sparse_grid() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.bounds(), None);
+        assert_eq!(grid.insert(Point::new(-1, 0), 'a'), None);
+        assert_eq!(grid.insert(Point::new(1, 1), 'b'), None);
+        assert_eq!(grid.insert(Point::new(1, 1), 'c'), Some('b'));
+        assert_eq!(grid.len(), 2);
+        assert_eq!(grid.bounds(), Some((Point::new(-1, 0), Point::new(1, 1))));
+        assert_eq!(grid.render(|cell| cell.copied().unwrap_or('.')), "a..\n..c\n");
+    }
+}
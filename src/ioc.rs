@@ -1,17 +1,185 @@
-use std::fs;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::Path;
+//! Reading and normalising input text.
 
-pub fn line_from_file(filename: impl AsRef<Path>) -> String {
-    fs::read_to_string(filename).expect("Unable to read input day 1")
+use std::{fmt, fs};
+use std::error::Error;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+use std::string::FromUtf8Error;
+
+/// The name standard input goes by in messages.
+pub const STDIN: &str = "<stdin>";
+
+/// Where the input of a day is read from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum InputSource {
+    /// A file, read as a whole.
+    File(PathBuf),
+    /// Standard input, read until it is closed.
+    Stdin,
 }
 
-pub fn lines_from_file(filename: impl AsRef<Path>) -> Vec<String> {
-    let file = File::open(filename).expect("no such file");
-    let buf = BufReader::new(file);
+impl InputSource {
+    /// Reads the input and normalises it.
+    pub fn load(&self, normalize: &Normalize) -> Result<String, InputError> {
+        match self {
+            InputSource::File(path) => line_from_file(path, normalize),
+            InputSource::Stdin => line_from_stdin(normalize),
+        }
+    }
+}
+
+impl fmt::Display for InputSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputSource::File(path) => write!(f, "{}", path.display()),
+            InputSource::Stdin => write!(f, "{}", STDIN),
+        }
+    }
+}
+
+/// Why an input could not be loaded.
+#[derive(Debug)]
+pub enum InputError {
+    /// The file, or standard input, could not be read.
+    Read {
+        /// The file, or [`STDIN`].
+        path: PathBuf,
+        /// The error reading it.
+        cause: io::Error,
+    },
+    /// The input is not text.
+    Encoding {
+        /// The file, or [`STDIN`].
+        path: PathBuf,
+        /// The error decoding it.
+        cause: FromUtf8Error,
+    },
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InputError::Read { path, cause } => write!(f, "unable to read input {}: {}", path.display(), cause),
+            InputError::Encoding { path, cause } => write!(f, "input {} is not valid UTF-8: {}", path.display(), cause),
+        }
+    }
+}
+
+impl Error for InputError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            InputError::Read { cause, .. } => Some(cause),
+            InputError::Encoding { cause, .. } => Some(cause),
+        }
+    }
+}
+
+/// How raw input text is cleaned up before it is handed to a puzzle.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Normalize {
+    /// Remove the line breaks at the end.
+    pub strip_trailing_newline: bool,
+    /// Turn Windows line breaks into `\n`.
+    pub crlf_to_lf: bool,
+    /// Drop lines that are empty or only whitespace.
+    pub skip_blank_lines: bool,
+}
+
+impl Normalize {
+    /// Leaves the text as it is.
+    pub const NONE: Normalize = Normalize {
+        strip_trailing_newline: false,
+        crlf_to_lf: false,
+        skip_blank_lines: false,
+    };
+
+    /// The text cleaned up as configured.
+    pub fn apply(&self, text: String) -> String {
+        let mut text = if self.crlf_to_lf { text.replace("\r\n", "\n") } else { text };
+
+        if self.skip_blank_lines {
+            text = text.split('\n')
+                .filter(|line| !line.trim().is_empty())
+                .collect::<Vec<&str>>()
+                .join("\n");
+        }
+
+        if self.strip_trailing_newline {
+            while text.ends_with('\n') || text.ends_with('\r') {
+                text.pop();
+            }
+        }
+
+        text
+    }
+}
+
+impl Default for Normalize {
+    fn default() -> Self {
+        Normalize {
+            strip_trailing_newline: true,
+            crlf_to_lf: true,
+            skip_blank_lines: true,
+        }
+    }
+}
+
+fn decode(path: &Path, bytes: Vec<u8>, normalize: &Normalize) -> Result<String, InputError> {
+    String::from_utf8(bytes)
+        .map(|text| normalize.apply(text))
+        .map_err(|cause| InputError::Encoding { path: path.to_path_buf(), cause })
+}
+
+/// Reads and normalises a whole file.
+pub fn line_from_file(filename: impl AsRef<Path>, normalize: &Normalize) -> Result<String, InputError> {
+    let path = filename.as_ref();
+    let bytes = fs::read(path)
+        .map_err(|cause| InputError::Read { path: path.to_path_buf(), cause })?;
+
+    decode(path, bytes, normalize)
+}
+
+/// Reads and normalises standard input.
+pub fn line_from_stdin(normalize: &Normalize) -> Result<String, InputError> {
+    let path = Path::new(STDIN);
+    let mut bytes = Vec::new();
+    io::stdin().read_to_end(&mut bytes)
+        .map_err(|cause| InputError::Read { path: path.to_path_buf(), cause })?;
+
+    decode(path, bytes, normalize)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use rstest::rstest;
+    use crate::ioc::{decode, InputError, line_from_file, Normalize};
+
+    #[rstest]
+    #[case("R2, L3\n", "R2, L3")]
+    #[case("R2, L3\r\n\r\n", "R2, L3")]
+    #[case("a\r\nb\r\n", "a\nb")]
+    #[case("a\n\n  \nb\n", "a\nb")]
+    fn test_normalize_default(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(Normalize::default().apply(input.to_string()), expected);
+    }
+
+    #[rstest]
+    fn test_normalize_none() {
+        assert_eq!(Normalize::NONE.apply("a\r\n\nb\n".to_string()), "a\r\n\nb\n");
+    }
+
+    #[rstest]
+    fn test_missing_file() {
+        let error = line_from_file("./res/no-such-input.txt", &Normalize::default()).unwrap_err();
+        assert!(matches!(error, InputError::Read { .. }));
+        assert!(error.to_string().contains("./res/no-such-input.txt"));
+    }
 
-    buf.lines()
-        .map(|l| l.expect("Could not parse line"))
-        .collect()
+    #[rstest]
+    fn test_invalid_utf8() {
+        let error = decode(Path::new("bad.txt"), vec![b'a', 0xff], &Normalize::default()).unwrap_err();
+        assert!(matches!(error, InputError::Encoding { .. }));
+        assert!(error.to_string().contains("bad.txt"));
+    }
 }
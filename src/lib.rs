@@ -0,0 +1,50 @@
+//! Solutions for Advent of Code 2016.
+//!
+//! Every `dayN` module exposes the model its input is parsed into, a `parse` function and the
+//! `part1` and `part2` solvers, plus a [`Solution`] implementation that turns their results into
+//! an [`Answer`] and is listed in the [`registry`]. The [`runner`] runs registered days with the
+//! inputs and parameters from the [`config`] and stops parts that run out of time with [`cancel`],
+//! [`timing`] measures them and [`verify`] compares their answers with the [`answers`] file;
+//! [`examples`] checks the puzzle examples kept in files. [`profiles`] holds the inputs of other
+//! accounts, [`output`] writes the results as JSON lines or CSV and [`report`] as Markdown or HTML
+//! for people, [`scaffold`] adds a new day from the `day.rs` template, [`generate`] makes random
+//! inputs of any size and [`watch`] notices when inputs change. Days parse their input with the
+//! [`parser`] toolkit and walk keypads, screens and streets with [`geometry`].
+
+#![warn(missing_docs)]
+
+pub mod answer;
+pub mod answers;
+pub mod cancel;
+pub mod config;
+pub mod day1;
+pub mod day2;
+pub mod day3;
+pub mod day4;
+pub mod day5;
+pub mod day6;
+pub mod day7;
+pub mod day8;
+pub mod day9;
+pub mod day10;
+pub mod error;
+pub mod examples;
+pub mod generate;
+pub mod geometry;
+pub mod ioc;
+pub mod output;
+pub mod parser;
+pub mod profiles;
+pub mod registry;
+pub mod report;
+pub mod runner;
+pub mod scaffold;
+pub mod solution;
+#[cfg(test)]
+mod testing;
+pub mod timing;
+pub mod verify;
+pub mod watch;
+
+pub use error::{Error, Result};
+pub use solution::{Answer, Puzzle, Solution};
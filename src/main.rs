@@ -1,177 +1,282 @@
-use chrono::{Duration, Local};
-use crate::ioc::{line_from_file, lines_from_file};
-
-mod day1;
-mod day2;
-mod day3;
-mod day4;
-mod day5;
-mod day6;
-mod day7;
-mod ioc;
-mod day8;
-mod day9;
-mod day10;
-
-fn duration(duration : Duration) -> String {
-    format!("{:02}:{:02}:{:02}.{:03}"
-            , duration.num_hours() % 24
-            , duration.num_minutes() % 60
-            , duration.num_seconds() % 60
-            , duration.num_milliseconds() % 1000)
+use std::process::ExitCode;
+use clap::Parser;
+use tracing::level_filters::LevelFilter;
+use tracing_subscriber::EnvFilter;
+use std::fs::{self, File};
+use std::io::{self, IsTerminal};
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+use adventofcode_2016_rust::{Answer, Error};
+use adventofcode_2016_rust::answers::Answers;
+use adventofcode_2016_rust::examples;
+use adventofcode_2016_rust::generate;
+use adventofcode_2016_rust::output::{self, Format, Writer};
+use adventofcode_2016_rust::ioc::InputSource;
+use adventofcode_2016_rust::profiles;
+use adventofcode_2016_rust::registry::DAYS;
+use adventofcode_2016_rust::report;
+use adventofcode_2016_rust::runner::{self, DayResult, PartResult};
+use adventofcode_2016_rust::scaffold;
+use adventofcode_2016_rust::timing::{self, format_duration, Stats};
+use adventofcode_2016_rust::verify;
+use adventofcode_2016_rust::watch;
+use crate::cli::{Cli, Command, CompareArgs, ExamplesArgs, GenerateArgs, NewDayArgs, ReportArgs, RunArgs, VerifyArgs, WatchArgs};
+
+mod cli;
+
+fn timing(stats: &Stats) -> String {
+    if stats.runs > 1 {
+        format!("{} (median of {} runs)", format_duration(stats.median), stats.runs)
+    } else {
+        format_duration(stats.median)
+    }
 }
 
-fn day1() {
-    println!("Day 1");
-    let input = line_from_file("./res/input-day1.txt");
-
-    let start = Local::now();
-    println!("part 1 - The Easter bunny is {} blocks away.", day1::part1(&input).unwrap());
-    let step = Local::now();
-    println!("part 2 - The Easter bunny is {} blocks away.", day1::part2(&input).unwrap());
-    let end = Local::now();
-
-    println!("Part 1 {:?}, and part 2 {:?}.", duration(step - start), duration(end - step));
+fn print_part(result: &PartResult) {
+    match &result.answer {
+        Ok(Answer::Screen(screen)) => {
+            println!("part {} -", result.part);
+            print!("{}", screen);
+        },
+        Ok(answer) => println!("part {} - {}", result.part, answer),
+        Err(e @ (Error::TimedOut { .. } | Error::NotImplemented)) => println!("part {} - {}", result.part, e),
+        Err(e) => println!("part {} - error: {}", result.part, e),
+    }
 }
 
-fn day2() {
-    println!("Day 2");
-    let input = lines_from_file("./res/input-day2.txt");
-
-    let start = Local::now();
-    println!("part 1 - The bathroom code is {}", day2::part1(&input));
-    let step = Local::now();
-    println!("part 2 - The bathroom code is {}", day2::part2(&input));
-    let end = Local::now();
+fn print_day(result: &DayResult) {
+    println!("Day {} - {}", result.day.number, result.day.title);
 
-    println!("Part 1 {:?}, and part 2 {:?}.", duration(step - start), duration(end - step));
-}
-
-fn day3() {
-    println!("Day 3");
-    let input = lines_from_file("./res/input-day3.txt");
+    if let Some(e) = &result.parse_error {
+        println!("error: {}", e);
+        return;
+    }
 
-    let start = Local::now();
-    println!("part 1 - The number of possible triangles is {}", day3::part1(&input));
-    let step = Local::now();
-    println!("part 2 - The number of possible triangles vertically grouped is {}", day3::part2(&input));
-    let end = Local::now();
+    for part in &result.parts {
+        print_part(part);
+    }
 
-    println!("Part 1 {:?}, and part 2 {:?}.", duration(step - start), duration(end - step));
+    let timings = result.parts.iter()
+        .map(|part| format!("part {} {}", part.part, timing(&part.timing)))
+        .collect::<Vec<String>>();
+    println!("Parse {}, {}.", timing(&result.parse), timings.join(", "));
+    println!();
 }
 
-fn day4() {
-    println!("Day 4");
-    let input = lines_from_file("./res/input-day4.txt");
-
-    let start = Local::now();
-    println!("part 1 - Sum of the sector IDs of the real rooms is {}", day4::part1(&input));
-    let step = Local::now();
-    println!("part 2 - Room for North Pole objects has sector ID {}", day4::part2(&input).unwrap());
-    let end = Local::now();
-
-    println!("Part 1 {:?}, and part 2 {:?}.", duration(step - start), duration(end - step));
+fn run(args: &RunArgs) -> Result<bool, String> {
+    let jobs = args.select_days()?;
+    let results = if args.format == Format::Text {
+        runner::run(&jobs, &args.options(), print_day)
+    } else {
+        let mut writer = Writer::new(args.format, io::stdout()).map_err(|e| e.to_string())?;
+        let mut written = Ok(());
+        let results = runner::run(&jobs, &args.options(), |result| {
+            for record in output::records(result) {
+                if written.is_ok() {
+                    written = writer.write(&record);
+                }
+            }
+        });
+        written.map_err(|e| e.to_string())?;
+        results
+    }.map_err(|e| e.to_string())?;
+
+    if args.timing_table && args.format == Format::Text {
+        print!("{}", timing::table(&results));
+    } else if args.timing_table {
+        eprint!("{}", timing::table(&results));
+    }
+
+    Ok(results.iter().all(|result| result.success()))
 }
 
-fn day5() {
-    println!("Day 5");
-    let input = "abbhdwsy";
-
-    let start = Local::now();
-    println!("part 1 - The password is {:?}", day5::part1(&input));
-    let step = Local::now();
-    println!("part 2 - The password is {:?}", day5::part2(&input));
-    let end = Local::now();
-
-    println!("Part 1 {:?}, and part 2 {:?}.", duration(step - start), duration(end - step));
+fn verify(args: &VerifyArgs) -> Result<bool, String> {
+    let answers = Answers::load(&args.answers).map_err(|e| e.to_string())?;
+    let jobs = args.run.select_days()?;
+    let results = runner::run(&jobs, &args.run.options(), |result| eprintln!("verified day {}", result.day.number))
+        .map_err(|e| e.to_string())?;
+
+    let checks = verify::verify(&results, &answers);
+    if args.run.format == Format::Text {
+        print!("{}", verify::table(&checks));
+    } else {
+        let mut writer = Writer::new(args.run.format, io::stdout()).map_err(|e| e.to_string())?;
+        let records = results.iter().flat_map(output::records);
+        for (record, check) in records.zip(&checks) {
+            writer.write(&record.checked(check)).map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(!checks.iter().any(|check| check.failed()))
 }
 
-fn day6() {
-    println!("Day 6");
-    let input = lines_from_file("./res/input-day6.txt");
+fn compare(args: &CompareArgs) -> Result<bool, String> {
+    if args.run.profile.is_some() || !args.run.inputs.is_empty() {
+        return Err("compare runs the inputs of every profile, select them with --profiles".to_string());
+    }
+
+    let names = if args.profiles.is_empty() {
+        let dir = DAYS.first().and_then(|day| Path::new(day.input).parent()).unwrap_or(Path::new("."));
+        std::iter::once(profiles::DEFAULT.to_string())
+            .chain(profiles::discover(dir).map_err(|e| e.to_string())?)
+            .collect()
+    } else {
+        args.profiles.clone()
+    };
+
+    let mut compared = Vec::new();
+    let mut success = true;
+    for name in names {
+        let mut run = args.run.clone();
+        run.profile = Some(name.clone());
+
+        // A profile only holds the inputs of the days its account solved.
+        let jobs: Vec<_> = run.select_days()?.into_iter()
+            .filter(|job| name == profiles::DEFAULT || matches!(&job.source, InputSource::File(path) if path.exists()))
+            .collect();
+        let results = runner::run(&jobs, &run.options(), |result| eprintln!("ran day {} for {}", result.day.number, name))
+            .map_err(|e| e.to_string())?;
+
+        success &= results.iter().all(|result| result.success());
+        compared.push((name, results));
+    }
+
+    print!("{}", profiles::table(&compared));
+    Ok(success)
+}
 
-    let start = Local::now();
-    println!("part 1 - The error-corrected version of the message being sent is {:?}", day6::part1(&input));
-    let step = Local::now();
-    println!("part 2 - The original message that Santa is trying to send is {:?}", day6::part2(&input));
-    let end = Local::now();
+fn examples(args: &ExamplesArgs) -> Result<bool, String> {
+    let examples = examples::discover(&args.dir, |day| args.days.iter().any(|selection| selection.contains(day)))
+        .map_err(|e| e.to_string())?;
+    let checked: Vec<_> = examples.iter().map(|example| (example, example.check())).collect();
 
-    println!("Part 1 {:?}, and part 2 {:?}.", duration(step - start), duration(end - step));
+    print!("{}", examples::table(&checked));
+    Ok(!checked.iter().any(|(_, checks)| checks.iter().any(|check| check.failed())))
 }
 
-fn day7() {
-    println!("Day 7");
-    let input = lines_from_file("./res/input-day7.txt");
-
-    let start = Local::now();
-    println!("part 1 - The number of IPs supporting TLS is {:?}", day7::part1(&input));
-    let step = Local::now();
-    println!("part 2 - The number of IPs supporting SSL is {:?}", day7::part2(&input));
-    let end = Local::now();
+fn new_day(args: &NewDayArgs) -> Result<bool, String> {
+    for path in scaffold::new_day(&args.root, args.day, &args.title).map_err(|e| e.to_string())? {
+        println!("wrote {}", path.display());
+    }
 
-    println!("Part 1 {:?}, and part 2 {:?}.", duration(step - start), duration(end - step));
+    Ok(true)
 }
 
-fn day8() {
-    println!("Day 8");
-    let input = lines_from_file("./res/input-day8.txt").iter()
-        .map(|line| day8::Command::from(line).unwrap())
-        .collect::<Vec<day8::Command>>();
+fn generate(args: &GenerateArgs) -> Result<bool, String> {
+    let input = generate::generate(args.day, args.size, args.seed).map_err(|e| e.to_string())?;
+    match &args.output {
+        Some(path) => fs::write(path, &input).map_err(|e| format!("unable to write {}: {}", path.display(), e))?,
+        None if input.ends_with('\n') => print!("{}", input),
+        None => println!("{}", input),
+    }
 
-    let start = Local::now();
-    println!("part 1 - The number of pixels lit is {:?}", day8::part1(&input));
-    let step = Local::now();
-    println!("part 2 - The screen shows:");
-    println!("{}", day8::part2(&input));
-    let end = Local::now();
+    Ok(true)
+}
 
-    println!("Part 1 {:?}, and part 2 {:?}.", duration(step - start), duration(end - step));
+fn watch(args: &WatchArgs) -> Result<bool, String> {
+    let jobs = args.run.select_days()?;
+    if jobs.iter().any(|job| job.source == InputSource::Stdin) {
+        return Err("watch reads inputs from files, not from stdin".to_string());
+    }
+
+    let options = args.run.options();
+    let mut stamps = None;
+    let mut previous = Vec::new();
+    loop {
+        let current = watch::stamps(&watch::files(&jobs, &args.dir));
+        let changed = stamps.as_ref().map(|stamps| watch::changed(stamps, &current));
+        if changed.as_ref().is_none_or(|changed| !changed.is_empty()) {
+            stamps = Some(current);
+            if let Some(changed) = changed {
+                let paths: Vec<String> = changed.iter().map(|path| path.display().to_string()).collect();
+                println!("changed {}", paths.join(", "));
+            }
+
+            // A missing input or a broken example is reported, and watched until it is fixed.
+            let results = runner::run(&jobs, &options, |_| {}).unwrap_or_else(|e| {
+                println!("error: {}", e);
+                Vec::new()
+            });
+            let examples = examples::discover(&args.dir, |day| jobs.iter().any(|job| job.day.number == day)).unwrap_or_else(|e| {
+                println!("error: {}", e);
+                Vec::new()
+            });
+            let checked: Vec<_> = examples.iter().map(|example| (example, example.check())).collect();
+
+            let answers = watch::answers(&results, &checked);
+            print!("{}", watch::report(&previous, &answers));
+            println!();
+            previous = answers;
+        }
+
+        thread::sleep(Duration::from_millis(args.interval));
+    }
 }
 
-fn day9() {
-    println!("Day 9");
-    let input = line_from_file("./res/input-day9.txt");
+fn report(args: &ReportArgs) -> Result<bool, String> {
+    let previous = args.previous.as_ref().map(report::load).transpose().map_err(|e| e.to_string())?;
+    let jobs = args.run.select_days()?;
+    let results = runner::run(&jobs, &args.run.options(), |result| eprintln!("ran day {}", result.day.number))
+        .map_err(|e| e.to_string())?;
 
-    let start = Local::now();
-    println!("part 1 - The size of the decrypted text is {:?}", day9::part1(&input).unwrap());
-    let step = Local::now();
-    println!("part 2 - The size of the decrypted text is {:?}", day9::part2(&input).unwrap());
-    let end = Local::now();
+    let format = args.report_format.unwrap_or_else(|| args.output.as_deref().map_or(report::Format::Markdown, report::Format::of));
+    let text = report::render(format, &report::entries(&results), previous.as_deref());
+    match &args.output {
+        Some(path) => fs::write(path, text).map_err(|e| format!("unable to write {}: {}", path.display(), e))?,
+        None => print!("{}", text),
+    }
 
-    println!("Part 1 {:?}, and part 2 {:?}.", duration(step - start), duration(end - step));
+    Ok(results.iter().all(|result| result.success()))
 }
 
-fn day10() {
-    println!("Day 10");
-    let input = lines_from_file("./res/input-day10.txt");
-
-    let start = Local::now();
-    println!("part 1 - The number of the bot that is responsible for comparing value-61 microchips with value-17 microchips {:?}", day10::part1(&input).unwrap());
-    let step = Local::now();
-    println!("part 2 - If you multiply together the values of one chip in each of outputs 0, 1, and 2 you get {:?}", day10::part2(&input).unwrap());
-    let end = Local::now();
-
-    println!("Part 1 {:?}, and part 2 {:?}.", duration(step - start), duration(end - step));
+/// Logs to stderr or the log file, at the level chosen with `-v` unless RUST_LOG is set.
+fn init_tracing(cli: &Cli) -> Result<(), String> {
+    let level = match cli.verbose {
+        0 => LevelFilter::ERROR,
+        1 => LevelFilter::INFO,
+        2 => LevelFilter::DEBUG,
+        _ => LevelFilter::TRACE,
+    };
+    let subscriber = tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::builder().with_default_directive(level.into()).from_env_lossy());
+
+    match &cli.log_file {
+        Some(path) => {
+            let file = File::create(path).map_err(|e| format!("unable to write {}: {}", path.display(), e))?;
+            subscriber.with_ansi(false).with_writer(Mutex::new(file)).init();
+        },
+        None => subscriber.with_ansi(io::stderr().is_terminal()).with_writer(io::stderr).init(),
+    }
+
+    Ok(())
 }
 
-fn main() {
-    day1();
-    println!();
-    day2();
-    println!();
-    day3();
-    println!();
-    day4();
-    println!();
-    day5();
-    println!();
-    day6();
-    println!();
-    day7();
-    println!();
-    day8();
-    println!();
-    day9();
-    println!();
-    day10();
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Err(message) = init_tracing(&cli) {
+        eprintln!("error: {}", message);
+        return ExitCode::FAILURE;
+    }
+
+    let outcome = match &cli.command {
+        None => run(&cli.run),
+        Some(Command::Run(args)) => run(args),
+        Some(Command::Verify(args)) => verify(args),
+        Some(Command::Compare(args)) => compare(args),
+        Some(Command::Examples(args)) => examples(args),
+        Some(Command::NewDay(args)) => new_day(args),
+        Some(Command::Generate(args)) => generate(args),
+        Some(Command::Watch(args)) => watch(args),
+        Some(Command::Report(args)) => report(args),
+    };
+
+    match outcome {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::FAILURE,
+        Err(message) => {
+            eprintln!("error: {}", message);
+            ExitCode::FAILURE
+        }
+    }
 }
@@ -0,0 +1,189 @@
+//! Machine-readable results, one record per day and part, as JSON lines or CSV.
+
+use std::io::Write;
+use std::str::FromStr;
+use serde::Serialize;
+use crate::error::{Error, Result};
+use crate::runner::DayResult;
+use crate::verify::Check;
+
+/// How results are written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Readable text, printed as days finish.
+    Text,
+    /// One JSON object per line.
+    Json,
+    /// CSV with a header line.
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("invalid format '{}', expected text, json or csv", s)),
+        }
+    }
+}
+
+/// The result of one part of a day; a day that failed to parse has a single record without a part.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Record {
+    /// The number of the day.
+    pub day: u8,
+    /// The part, `None` when parsing failed.
+    pub part: Option<u8>,
+    /// `ok`, `error` or `skipped`, or the status of the check when verified.
+    pub status: String,
+    /// The answer, when the part succeeded.
+    pub answer: Option<String>,
+    /// The kind of the answer, see [`Answer::kind`](crate::answer::Answer::kind).
+    #[serde(rename = "type")]
+    pub kind: Option<&'static str>,
+    /// The expected answer, when verified.
+    pub expected: Option<String>,
+    /// Why parsing or the part failed.
+    pub error: Option<String>,
+    /// The median time to parse, in nanoseconds.
+    pub parse_ns: u128,
+    /// The median time to solve the part, in nanoseconds.
+    pub solve_ns: Option<u128>,
+}
+
+impl Record {
+    /// Replaces the status of the run with the outcome of the check.
+    pub fn checked(self, check: &Check) -> Record {
+        Record { status: check.status.to_string().to_lowercase(), expected: check.expected.as_ref().map(|e| e.to_string()), ..self }
+    }
+}
+
+/// The records of a day, using the median timings.
+pub fn records(result: &DayResult) -> Vec<Record> {
+    let day = result.day.number;
+    let parse_ns = result.parse.median.as_nanos();
+
+    if let Some(e) = &result.parse_error {
+        return vec![Record {
+            day, part: None, status: "error".to_string(), answer: None, kind: None,
+            expected: None, error: Some(e.to_string()), parse_ns, solve_ns: None,
+        }];
+    }
+
+    result.parts.iter()
+        .map(|part| {
+            let (status, answer, kind, error) = match &part.answer {
+                Ok(answer) => ("ok", Some(answer.to_string()), Some(answer.kind()), None),
+                Err(e @ Error::NotImplemented) => ("skipped", None, None, Some(e.to_string())),
+                Err(e) => ("error", None, None, Some(e.to_string())),
+            };
+            Record {
+                day, part: Some(part.part), status: status.to_string(), answer, kind,
+                expected: None, error, parse_ns, solve_ns: Some(part.timing.median.as_nanos()),
+            }
+        })
+        .collect()
+}
+
+/// Writes records in one of the machine-readable formats; the CSV header is written once.
+pub enum Writer<W: Write> {
+    /// Writes JSON lines.
+    Json(W),
+    /// Writes CSV.
+    Csv(Box<csv::Writer<W>>),
+}
+
+fn io(e: impl std::fmt::Display) -> Error {
+    Error::InvalidState(format!("unable to write results: {}", e))
+}
+
+impl<W: Write> Writer<W> {
+    /// A writer for `format`, which must not be text.
+    pub fn new(format: Format, out: W) -> Result<Writer<W>> {
+        match format {
+            Format::Text => Err(Error::InvalidState("text is not a machine-readable format".to_string())),
+            Format::Json => Ok(Writer::Json(out)),
+            Format::Csv => Ok(Writer::Csv(Box::new(csv::Writer::from_writer(out)))),
+        }
+    }
+
+    /// Writes and flushes a record, so results show up as soon as a day is done.
+    pub fn write(&mut self, record: &Record) -> Result<()> {
+        match self {
+            Writer::Json(out) => {
+                serde_json::to_writer(&mut *out, record).map_err(io)?;
+                writeln!(out).map_err(io)?;
+                out.flush().map_err(io)
+            },
+            Writer::Csv(csv) => {
+                csv.serialize(record).map_err(io)?;
+                csv.flush().map_err(io)
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use crate::error::Error;
+    use crate::output::{Format, Record, records, Writer};
+    use crate::runner::DayResult;
+
+    fn result() -> DayResult {
+        DayResult::fixture(1, 10, vec![(Ok(146.into()), 20), (Err(Error::NotFound("twice".to_string())), 30)])
+    }
+
+    fn write(format: Format, records: &[Record]) -> String {
+        let mut out = Vec::new();
+        let mut writer = Writer::new(format, &mut out).unwrap();
+        for record in records {
+            writer.write(record).unwrap();
+        }
+        drop(writer);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[rstest]
+    #[case("text", Format::Text)]
+    #[case("json", Format::Json)]
+    #[case("csv", Format::Csv)]
+    fn test_format(#[case] input: &str, #[case] expected: Format) {
+        assert_eq!(input.parse::<Format>().unwrap(), expected);
+        assert!("yaml".parse::<Format>().is_err());
+    }
+
+    #[rstest]
+    fn test_records_parse_error() {
+        let result = DayResult { parse_error: Some(Error::parse(3, "bad")), ..DayResult::fixture(1, 10, vec![]) };
+        let records = records(&result);
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].part, None);
+        assert_eq!(records[0].status, "error");
+    }
+
+    #[rstest]
+    fn test_json() {
+        assert_eq!(write(Format::Json, &records(&result())),
+                   "{\"day\":1,\"part\":1,\"status\":\"ok\",\"answer\":\"146\",\"type\":\"integer\",\"expected\":null,\"error\":null,\"parse_ns\":10,\"solve_ns\":20}\n\
+                    {\"day\":1,\"part\":2,\"status\":\"error\",\"answer\":null,\"type\":null,\"expected\":null,\"error\":\"not found: twice\",\"parse_ns\":10,\"solve_ns\":30}\n");
+    }
+
+    #[rstest]
+    fn test_csv() {
+        assert_eq!(write(Format::Csv, &records(&result())),
+                   "day,part,status,answer,type,expected,error,parse_ns,solve_ns\n\
+                    1,1,ok,146,integer,,,10,20\n\
+                    1,2,error,,,,not found: twice,10,30\n");
+    }
+
+    #[rstest]
+    fn test_csv_screen_is_quoted() {
+        let record = Record { answer: Some("#.\n.#".to_string()), ..records(&result()).remove(0) };
+        assert!(write(Format::Csv, &[record]).contains("\"#.\n.#\""));
+    }
+}
@@ -0,0 +1,377 @@
+//! A small parser-combinator toolkit. Parsers work on an [`Input`] and fail with a parse error
+//! holding the line and column where the input did not match.
+
+use std::str::FromStr;
+use crate::error::{Error, Result};
+
+/// The text being parsed and how far parsing got; the line and column are kept up to date as
+/// characters are consumed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Input<'a> {
+    text: &'a str,
+    offset: usize,
+    line: usize,
+    column: usize,
+}
+
+impl<'a> Input<'a> {
+    /// The whole text, at line 1, column 1.
+    pub fn new(text: &'a str) -> Input<'a> {
+        Input { text, offset: 0, line: 1, column: 1 }
+    }
+
+    /// Consumes the next `len` bytes, which end on a character boundary.
+    fn advance(&mut self, len: usize) {
+        let consumed = &self.text[self.offset..self.offset + len];
+        match consumed.rfind('\n') {
+            Some(idx) => {
+                self.line += consumed.matches('\n').count();
+                self.column = consumed[idx + 1..].chars().count() + 1;
+            },
+            None => self.column += consumed.chars().count(),
+        }
+        self.offset += len;
+    }
+
+    /// The text that is not parsed yet.
+    pub fn rest(&self) -> &'a str {
+        &self.text[self.offset..]
+    }
+
+    /// Whether all of the text is parsed.
+    pub fn is_empty(&self) -> bool {
+        self.offset == self.text.len()
+    }
+
+    /// Consumes up to `count` characters and returns them.
+    pub fn take(&mut self, count: usize) -> &'a str {
+        let rest = self.rest();
+        let len = rest.char_indices().nth(count).map_or(rest.len(), |(idx, _)| idx);
+        self.advance(len);
+        &rest[..len]
+    }
+
+    /// The (1-based) line and column of the next character.
+    pub fn position(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    /// A parse error at the next character.
+    pub fn error(&self, message: impl Into<String>) -> Error {
+        let (line, column) = self.position();
+        Error::Parse { line, column, message: message.into() }
+    }
+}
+
+/// Something that parses a `T` from the start of the input, consuming what it matched.
+pub trait Parser<T> {
+    /// Parses a `T`, leaving the input after it.
+    fn parse(&self, input: &mut Input) -> Result<T>;
+}
+
+impl<T, F> Parser<T> for F where F: Fn(&mut Input) -> Result<T> {
+    fn parse(&self, input: &mut Input) -> Result<T> {
+        self(input)
+    }
+}
+
+fn position(e: &Error) -> (usize, usize) {
+    match e {
+        Error::Parse { line, column, .. } => (*line, *column),
+        _ => (usize::MAX, usize::MAX),
+    }
+}
+
+/// Runs the parser on a copy of the input; the input only moves on when the parser succeeds.
+/// A failure that did not get past the start of the input gives `None`, so the caller can try
+/// something else, any other failure is passed on.
+fn attempt<T>(parser: &impl Parser<T>, input: &mut Input) -> Result<Option<T>> {
+    let mut copy = *input;
+    match parser.parse(&mut copy) {
+        Ok(value) => {
+            *input = copy;
+            Ok(Some(value))
+        },
+        Err(e) if position(&e) <= input.position() => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Matches exactly `expected`.
+pub fn literal(expected: &'static str) -> impl Parser<()> {
+    move |input: &mut Input| {
+        if input.rest().starts_with(expected) {
+            input.advance(expected.len());
+            Ok(())
+        } else {
+            Err(input.error(format!("expected '{}'", expected)))
+        }
+    }
+}
+
+/// One or more characters matching `predicate`, described as `what` when there are none.
+pub fn take_while1(what: &'static str, predicate: impl Fn(char) -> bool) -> impl Parser<String> {
+    move |input: &mut Input| {
+        let len: usize = input.rest().chars().take_while(|ch| predicate(*ch)).map(char::len_utf8).sum();
+        if len == 0 {
+            return Err(input.error(format!("expected {}", what)));
+        }
+
+        let matched = &input.rest()[..len];
+        input.advance(len);
+        Ok(matched.to_string())
+    }
+}
+
+/// One or more letters.
+pub fn letters() -> impl Parser<String> {
+    take_while1("a letter", char::is_alphabetic)
+}
+
+/// A letter or underscore followed by letters, digits and underscores.
+pub fn identifier() -> impl Parser<String> {
+    move |input: &mut Input| {
+        if !input.rest().starts_with(|ch: char| ch.is_alphabetic() || ch == '_') {
+            return Err(input.error("expected an identifier"));
+        }
+        take_while1("an identifier", |ch| ch.is_alphanumeric() || ch == '_').parse(input)
+    }
+}
+
+/// A sequence of digits, converted to `T`; a number too large for `T` fails at its first digit.
+pub fn number<T: FromStr>() -> impl Parser<T> {
+    move |input: &mut Input| {
+        let start = *input;
+        let digits = take_while1("a number", |ch| ch.is_ascii_digit()).parse(input)?;
+        digits.parse::<T>().map_err(|_| start.error(format!("invalid number '{}'", digits)))
+    }
+}
+
+/// The end of the input.
+pub fn end() -> impl Parser<()> {
+    move |input: &mut Input| {
+        if input.is_empty() {
+            Ok(())
+        } else {
+            Err(input.error(format!("unexpected '{}'", input.rest().chars().next().unwrap_or_default())))
+        }
+    }
+}
+
+/// `parser`, with `f` applied to what it parsed.
+pub fn map<A, B>(parser: impl Parser<A>, f: impl Fn(A) -> B) -> impl Parser<B> {
+    move |input: &mut Input| parser.parse(input).map(&f)
+}
+
+/// `first` and then `second`.
+pub fn pair<A, B>(first: impl Parser<A>, second: impl Parser<B>) -> impl Parser<(A, B)> {
+    move |input: &mut Input| Ok((first.parse(input)?, second.parse(input)?))
+}
+
+/// `parser`, after `prefix`.
+pub fn preceded<A, B>(prefix: impl Parser<A>, parser: impl Parser<B>) -> impl Parser<B> {
+    move |input: &mut Input| {
+        prefix.parse(input)?;
+        parser.parse(input)
+    }
+}
+
+/// `parser`, followed by `suffix`.
+pub fn terminated<A, B>(parser: impl Parser<A>, suffix: impl Parser<B>) -> impl Parser<A> {
+    move |input: &mut Input| {
+        let value = parser.parse(input)?;
+        suffix.parse(input)?;
+        Ok(value)
+    }
+}
+
+/// `parser`, between `open` and `close`.
+pub fn delimited<A, B, C>(open: impl Parser<A>, parser: impl Parser<B>, close: impl Parser<C>) -> impl Parser<B> {
+    preceded(open, terminated(parser, close))
+}
+
+/// Zero or more times `parser`, until it fails without consuming input.
+pub fn many<T>(parser: impl Parser<T>) -> impl Parser<Vec<T>> {
+    move |input: &mut Input| {
+        let mut values = Vec::new();
+        while !input.is_empty() {
+            match attempt(&parser, input)? {
+                Some(value) => values.push(value),
+                None => break,
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// One or more `item`s with a `separator` between each of them.
+pub fn separated<T, S>(item: impl Parser<T>, separator: impl Parser<S>) -> impl Parser<Vec<T>> {
+    move |input: &mut Input| {
+        let mut items = vec![item.parse(input)?];
+        while attempt(&separator, input)?.is_some() {
+            items.push(item.parse(input)?);
+        }
+        Ok(items)
+    }
+}
+
+/// A tuple of parsers producing the same type, to choose from with [`alt`].
+pub trait Choice<T> {
+    /// The value and the input after it of the first parser that matches; the error of every
+    /// parser that did not match is added to `errors`.
+    fn first<'a>(&self, input: &Input<'a>, errors: &mut Vec<Error>) -> Option<(T, Input<'a>)>;
+}
+
+macro_rules! choice {
+    ($($parser:ident $idx:tt),+) => {
+        impl<T, $($parser: Parser<T>),+> Choice<T> for ($($parser,)+) {
+            fn first<'a>(&self, input: &Input<'a>, errors: &mut Vec<Error>) -> Option<(T, Input<'a>)> {
+                $(
+                    let mut copy = *input;
+                    match self.$idx.parse(&mut copy) {
+                        Ok(value) => return Some((value, copy)),
+                        Err(e) => errors.push(e),
+                    }
+                )+
+                None
+            }
+        }
+    };
+}
+
+choice!(A 0, B 1);
+choice!(A 0, B 1, C 2);
+choice!(A 0, B 1, C 2, D 3);
+
+/// The first of the parsers that matches. When none does, the error of the parser that got
+/// furthest is reported, or what each of them expected if they all failed at the same spot.
+pub fn alt<T>(parsers: impl Choice<T>) -> impl Parser<T> {
+    move |input: &mut Input| {
+        let mut errors = Vec::new();
+        if let Some((value, rest)) = parsers.first(input, &mut errors) {
+            *input = rest;
+            return Ok(value);
+        }
+
+        let (line, column) = errors.iter().map(position).max().unwrap_or_else(|| input.position());
+        let mut furthest: Vec<Error> = errors.into_iter().filter(|e| position(e) == (line, column)).collect();
+        if furthest.len() <= 1 {
+            return Err(furthest.pop().unwrap_or_else(|| input.error("nothing to match")));
+        }
+
+        let expected: Vec<String> = furthest.iter()
+            .filter_map(|e| match e {
+                Error::Parse { message, .. } => Some(message.strip_prefix("expected ").unwrap_or(message).to_string()),
+                _ => None,
+            })
+            .collect();
+        let (last, first) = expected.split_last().unwrap();
+        Err(Error::Parse { line, column, message: format!("expected {} or {}", first.join(", "), last) })
+    }
+}
+
+/// Parses all of `text`; anything left after the parser is done is an error.
+pub fn parse_all<T>(parser: &impl Parser<T>, text: &str) -> Result<T> {
+    let mut input = Input::new(text);
+    let value = parser.parse(&mut input)?;
+    end().parse(&mut input)?;
+    Ok(value)
+}
+
+/// Parses every line of `text` on its own, with errors positioned in the full text.
+pub fn lines<T>(parser: &impl Parser<T>, text: &str) -> Result<Vec<T>> {
+    text.lines()
+        .enumerate()
+        .map(|(idx, line)| parse_all(parser, line).map_err(|e| e.at(idx + 1, 1)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use crate::error::Error;
+    use crate::parser::{alt, delimited, identifier, Input, letters, lines, literal, many, map, number, pair, parse_all, Parser, preceded, separated};
+
+    fn error_at<T: std::fmt::Debug>(result: crate::error::Result<T>) -> (usize, usize, String) {
+        match result {
+            Err(Error::Parse { line, column, message }) => (line, column, message),
+            other => panic!("expected a parse error, got {:?}", other),
+        }
+    }
+
+    #[rstest]
+    fn test_input() {
+        let mut input = Input::new("ab\ncdé\nf");
+        assert_eq!(input.take(5), "ab\ncd");
+        assert_eq!(input.position(), (2, 3));
+        assert_eq!(input.take(1), "é");
+        assert_eq!(input.position(), (2, 4));
+        assert_eq!(input.take(10), "\nf");
+        assert_eq!(input.position(), (3, 2));
+        assert!(input.is_empty());
+    }
+
+    #[rstest]
+    fn test_number() {
+        assert_eq!(parse_all(&number::<u8>(), "42").unwrap(), 42);
+        assert_eq!(error_at(parse_all(&number::<u8>(), "x")), (1, 1, "expected a number".to_string()));
+        assert_eq!(error_at(parse_all(&number::<u8>(), "256")), (1, 1, "invalid number '256'".to_string()));
+        assert_eq!(error_at(parse_all(&number::<u8>(), "25x")), (1, 3, "unexpected 'x'".to_string()));
+    }
+
+    #[rstest]
+    fn test_sequence() {
+        let parser = pair(preceded(literal("rect "), number::<u32>()), preceded(literal("x"), number::<u32>()));
+        assert_eq!(parse_all(&parser, "rect 3x2").unwrap(), (3, 2));
+        assert_eq!(error_at(parse_all(&parser, "rect 3y2")), (1, 7, "expected 'x'".to_string()));
+    }
+
+    #[rstest]
+    fn test_identifier() {
+        assert_eq!(parse_all(&identifier(), "bot_12").unwrap(), "bot_12");
+        assert!(parse_all(&identifier(), "12bot").is_err());
+    }
+
+    #[rstest]
+    fn test_separated() {
+        let parser = separated(number::<u32>(), literal(", "));
+        assert_eq!(parse_all(&parser, "1, 2, 3").unwrap(), vec![1, 2, 3]);
+        assert_eq!(error_at(parse_all(&parser, "1, 2, x")), (1, 7, "expected a number".to_string()));
+        assert_eq!(error_at(parse_all(&parser, "1, 2 3")), (1, 5, "unexpected ' '".to_string()));
+    }
+
+    #[rstest]
+    fn test_many() {
+        let parser = pair(letters(), many(pair(delimited(literal("["), letters(), literal("]")), letters())));
+        assert_eq!(parse_all(&parser, "ab[cd]ef").unwrap().1.len(), 1);
+        assert_eq!(error_at(parse_all(&parser, "ab[cd")), (1, 6, "expected ']'".to_string()));
+    }
+
+    #[rstest]
+    fn test_alt() {
+        let bot = map(preceded(literal("bot "), number::<u8>()), |id| id as i32);
+        let output = map(preceded(literal("output "), number::<u8>()), |id| -(id as i32));
+        let target = alt((bot, output));
+        assert_eq!(parse_all(&target, "bot 3").unwrap(), 3);
+        assert_eq!(parse_all(&target, "output 2").unwrap(), -2);
+        assert_eq!(error_at(parse_all(&target, "output x")), (1, 8, "expected a number".to_string()));
+        assert_eq!(error_at(parse_all(&target, "bin 1")), (1, 1, "expected 'bot ' or 'output '".to_string()));
+    }
+
+    #[rstest]
+    fn test_lines() {
+        assert_eq!(lines(&number::<u32>(), "1\n2").unwrap(), vec![1, 2]);
+        assert_eq!(error_at(lines(&number::<u32>(), "1\n2\n3a")), (3, 2, "unexpected 'a'".to_string()));
+    }
+
+    #[rstest]
+    fn test_closure() {
+        let even = |input: &mut Input| {
+            let start = *input;
+            let n = number::<u32>().parse(input)?;
+            if n % 2 == 0 { Ok(n) } else { Err(start.error("expected an even number")) }
+        };
+        assert_eq!(parse_all(&even, "4").unwrap(), 4);
+        assert!(parse_all(&even, "5").is_err());
+    }
+}
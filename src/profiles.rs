@@ -0,0 +1,159 @@
+//! Named input sets, one per account, stored as `res/<profile>/input-dayN.txt`.
+
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::error::{Error, Result};
+use crate::registry::Day;
+use crate::runner::DayResult;
+use crate::timing::format_duration;
+use crate::verify::summary;
+
+/// The name used for the inputs that are not part of a named profile.
+pub const DEFAULT: &str = "default";
+
+/// The input of the day in the profile: the registered input file, in the directory of the profile.
+pub fn input(day: &Day, profile: &str) -> PathBuf {
+    let registered = Path::new(day.input);
+    let dir = registered.parent().unwrap_or(Path::new("."));
+    match registered.file_name() {
+        Some(name) => dir.join(profile).join(name),
+        None => dir.join(profile),
+    }
+}
+
+/// The profiles below `dir`: every subdirectory holding at least one `input-dayN.txt`, sorted by name.
+pub fn discover(dir: impl AsRef<Path>) -> Result<Vec<String>> {
+    let dir = dir.as_ref();
+    let unreadable = |e: std::io::Error| Error::InvalidState(format!("unable to list {}: {}", dir.display(), e));
+
+    let mut profiles = Vec::new();
+    for entry in fs::read_dir(dir).map_err(unreadable)? {
+        let path = entry.map_err(unreadable)?.path();
+        if !path.is_dir() {
+            continue;
+        }
+
+        let has_input = fs::read_dir(&path).map_err(unreadable)?
+            .filter_map(|entry| entry.ok())
+            .any(|entry| entry.file_name().to_str().is_some_and(|name| name.starts_with("input-day") && name.ends_with(".txt")));
+        if let (true, Some(name)) = (has_input, path.file_name().and_then(|name| name.to_str())) {
+            profiles.push(name.to_string());
+        }
+    }
+
+    profiles.sort();
+    Ok(profiles)
+}
+
+fn cell(result: Option<&DayResult>, part: u8) -> String {
+    let Some(result) = result else { return "-".to_string() };
+    if let Some(e) = &result.parse_error {
+        return format!("error: {}", e);
+    }
+
+    match result.parts.iter().find(|p| p.part == part) {
+        Some(p) => match &p.answer {
+            Ok(answer) => format!("{} ({})", summary(&answer.to_string()), format_duration(p.timing.median)),
+            Err(e) => format!("error: {}", e),
+        },
+        None => "-".to_string(),
+    }
+}
+
+/// Renders the answers and median timings of every profile side by side, one row per day and part.
+pub fn table(profiles: &[(String, Vec<DayResult>)]) -> String {
+    let mut days: Vec<u8> = profiles.iter()
+        .flat_map(|(_, results)| results.iter().map(|result| result.day.number))
+        .collect();
+    days.sort();
+    days.dedup();
+
+    let mut rows = Vec::new();
+    for day in days {
+        let results: Vec<Option<&DayResult>> = profiles.iter()
+            .map(|(_, results)| results.iter().find(|result| result.day.number == day))
+            .collect();
+        let mut parts: Vec<u8> = results.iter().flatten()
+            .flat_map(|result| result.parts.iter().map(|part| part.part))
+            .collect();
+        parts.sort();
+        parts.dedup();
+        if parts.is_empty() {
+            parts.push(1);
+        }
+
+        for part in parts {
+            rows.push((day, part, results.iter().map(|result| cell(*result, part)).collect::<Vec<String>>()));
+        }
+    }
+
+    let widths: Vec<usize> = profiles.iter().enumerate()
+        .map(|(idx, (name, _))| rows.iter().map(|(_, _, cells)| cells[idx].chars().count()).chain([name.chars().count()]).max().unwrap_or(0))
+        .collect();
+
+    let mut table = String::new();
+    let mut line = format!("{:>3} {:>4}", "day", "part");
+    for ((name, _), width) in profiles.iter().zip(&widths) {
+        write!(line, "  {:<width$}", name, width = width).unwrap();
+    }
+    writeln!(table, "{}", line.trim_end()).unwrap();
+
+    for (day, part, cells) in rows {
+        let mut line = format!("{:>3} {:>4}", day, part);
+        for (cell, width) in cells.iter().zip(&widths) {
+            write!(line, "  {:<width$}", cell, width = width).unwrap();
+        }
+        writeln!(table, "{}", line.trim_end()).unwrap();
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+    use rstest::rstest;
+    use crate::profiles::{discover, input, table};
+    use crate::registry::DAYS;
+    use crate::runner::DayResult;
+    use crate::testing::TempDir;
+
+    fn result(day: u8, answers: &[&str]) -> DayResult {
+        DayResult::fixture(day, 0, answers.iter().map(|answer| (Ok(answer.parse().unwrap()), 0)).collect())
+    }
+
+    #[rstest]
+    fn test_input() {
+        assert_eq!(input(&DAYS[2], "alice"), PathBuf::from("./res/alice/input-day3.txt"));
+    }
+
+    #[rstest]
+    fn test_discover() {
+        let dir = TempDir::new("profiles");
+        for (profile, file) in [("bob", "input-day1.txt"), ("alice", "input-day3.txt"), ("notes", "todo.txt")] {
+            fs::create_dir_all(dir.join(profile)).unwrap();
+            fs::write(dir.join(profile).join(file), "").unwrap();
+        }
+        fs::write(dir.join("input-day1.txt"), "").unwrap();
+
+        assert_eq!(discover(&dir).unwrap(), vec!["alice", "bob"]);
+    }
+
+    #[rstest]
+    fn test_table() {
+        let profiles = vec![
+            ("default".to_string(), vec![result(1, &["146", "131"]), result(8, &["119", "#.\n.#\n"])]),
+            ("alice".to_string(), vec![result(1, &["12", "4"])]),
+        ];
+
+        assert_eq!(table(&profiles), "\
+day part  default           alice
+  1    1  146 (0 ns)        12 (0 ns)
+  1    2  131 (0 ns)        4 (0 ns)
+  8    1  119 (0 ns)        -
+  8    2  <2 lines> (0 ns)  -
+");
+    }
+}
@@ -0,0 +1,30 @@
+//! The registry of implemented days.
+
+use crate::solution::Puzzle;
+use crate::{day1, day10, day2, day3, day4, day5, day6, day7, day8, day9};
+
+/// A day of the calendar and its solution.
+pub struct Day {
+    /// The day of December.
+    pub number: u8,
+    /// The title of the puzzle.
+    pub title: &'static str,
+    /// The default input file.
+    pub input: &'static str,
+    /// The solution.
+    pub puzzle: &'static dyn Puzzle,
+}
+
+/// Every implemented day, in order.
+pub static DAYS: &[Day] = &[
+    Day { number: 1, title: "No Time for a Taxicab", input: "./res/input-day1.txt", puzzle: &day1::Day1 },
+    Day { number: 2, title: "Bathroom Security", input: "./res/input-day2.txt", puzzle: &day2::Day2 },
+    Day { number: 3, title: "Squares With Three Sides", input: "./res/input-day3.txt", puzzle: &day3::Day3 },
+    Day { number: 4, title: "Security Through Obscurity", input: "./res/input-day4.txt", puzzle: &day4::Day4 },
+    Day { number: 5, title: "How About a Nice Game of Chess?", input: "./res/input-day5.txt", puzzle: &day5::Day5 },
+    Day { number: 6, title: "Signals and Noise", input: "./res/input-day6.txt", puzzle: &day6::Day6 },
+    Day { number: 7, title: "Internet Protocol Version 7", input: "./res/input-day7.txt", puzzle: &day7::Day7 },
+    Day { number: 8, title: "Two-Factor Authentication", input: "./res/input-day8.txt", puzzle: &day8::Day8 },
+    Day { number: 9, title: "Explosives in Cyberspace", input: "./res/input-day9.txt", puzzle: &day9::Day9 },
+    Day { number: 10, title: "Balance Bots", input: "./res/input-day10.txt", puzzle: &day10::Day10 },
+];
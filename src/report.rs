@@ -0,0 +1,390 @@
+//! A report of a run for people, in Markdown or as a self-contained HTML page, with the answers,
+//! timings and screens, optionally compared with an earlier report.
+//!
+//! Every report embeds its results as JSON, so it can be read back as the earlier report of a
+//! later run.
+
+use std::fmt::Write;
+use std::path::Path;
+use std::str::FromStr;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use crate::error::{Error, Result};
+use crate::ioc::{line_from_file, Normalize};
+use crate::output::records;
+use crate::runner::DayResult;
+use crate::timing::format_duration;
+
+const TITLE: &str = "Advent of Code 2016";
+const MARKDOWN_DATA: (&str, &str) = ("<!-- aoc-report ", " -->");
+const HTML_DATA: (&str, &str) = ("<script type=\"application/json\" id=\"aoc-report\">", "</script>");
+
+/// How a report is written.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Markdown tables.
+    Markdown,
+    /// A single HTML page with its own styles.
+    Html,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "markdown" | "md" => Ok(Format::Markdown),
+            "html" => Ok(Format::Html),
+            _ => Err(format!("invalid report format '{}', expected markdown or html", s)),
+        }
+    }
+}
+
+impl Format {
+    /// HTML for a `.html` or `.htm` file, Markdown for anything else.
+    pub fn of(path: &Path) -> Format {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("html" | "htm") => Format::Html,
+            _ => Format::Markdown,
+        }
+    }
+}
+
+/// The result of one part of a day as kept in a report; a day that failed to parse has a single
+/// entry without a part.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    /// The number of the day.
+    pub day: u8,
+    /// The title of the puzzle.
+    pub title: String,
+    /// The part, `None` when parsing failed.
+    pub part: Option<u8>,
+    /// The answer, when the part succeeded.
+    pub answer: Option<String>,
+    /// Why parsing or the part failed.
+    pub error: Option<String>,
+    /// The median time to parse, in nanoseconds.
+    pub parse_ns: u128,
+    /// The median time to solve the part, in nanoseconds.
+    pub solve_ns: Option<u128>,
+    /// Whether the part is not implemented yet.
+    #[serde(default)]
+    pub skipped: bool,
+}
+
+impl Entry {
+    fn outcome(&self) -> String {
+        match (&self.answer, &self.error) {
+            _ if self.skipped => "skipped".to_string(),
+            (Some(answer), _) => answer.clone(),
+            (None, error) => format!("error: {}", error.as_deref().unwrap_or("unknown")),
+        }
+    }
+
+    fn time(&self) -> String {
+        format_duration(nanos(self.solve_ns.unwrap_or(self.parse_ns)))
+    }
+}
+
+fn nanos(nanos: u128) -> Duration {
+    Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+}
+
+/// The entries of the results, using the median timings.
+pub fn entries(results: &[DayResult]) -> Vec<Entry> {
+    results.iter()
+        .flat_map(|result| records(result).into_iter().map(move |record| Entry {
+            day: record.day,
+            title: result.day.title.to_string(),
+            part: record.part,
+            skipped: record.status == "skipped",
+            answer: record.answer,
+            error: record.error,
+            parse_ns: record.parse_ns,
+            solve_ns: record.solve_ns,
+        }))
+        .collect()
+}
+
+/// The entries embedded in a report written earlier.
+pub fn parse(text: &str) -> Result<Vec<Entry>> {
+    let data = [MARKDOWN_DATA, HTML_DATA].iter()
+        .find_map(|(start, end)| {
+            let from = text.find(start)? + start.len();
+            Some(&text[from..from + text[from..].find(end)?])
+        })
+        .ok_or_else(|| Error::NotFound("results embedded in the report".to_string()))?;
+
+    serde_json::from_str(data).map_err(|e| Error::parse(e.column(), e.to_string()).at(e.line(), 1))
+}
+
+/// Reads the results embedded in the report at `path`.
+pub fn load(path: impl AsRef<Path>) -> Result<Vec<Entry>> {
+    let path = path.as_ref();
+    parse(&line_from_file(path, &Normalize::NONE)?)
+        .map_err(|e| Error::InvalidState(format!("{} is not a readable report: {}", path.display(), e)))
+}
+
+/// The report as rows of cells, a total line and the screens, before it is rendered.
+struct Layout {
+    header: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+    total: String,
+    screens: Vec<(String, String)>,
+}
+
+fn total(entries: &[Entry]) -> (usize, Duration) {
+    let mut days: Vec<u8> = entries.iter().map(|entry| entry.day).collect();
+    days.dedup();
+
+    // Every entry of a day repeats the time it took to parse.
+    let parse: u128 = days.iter().filter_map(|day| entries.iter().find(|entry| entry.day == *day)).map(|entry| entry.parse_ns).sum();
+    let solve: u128 = entries.iter().filter_map(|entry| entry.solve_ns).sum();
+    (days.len(), nanos(parse + solve))
+}
+
+/// The number of parts solved and the number of parts run; skipped parts are left out, and a day
+/// that failed to parse counts as the parts the other days ran, or both when there are none.
+fn solved(entries: &[Entry]) -> (usize, usize) {
+    let mut parts: Vec<u8> = entries.iter().filter_map(|entry| entry.part).collect();
+    parts.sort();
+    parts.dedup();
+    let per_day = if parts.is_empty() { 2 } else { parts.len() };
+
+    let counted = entries.iter().filter(|entry| !entry.skipped);
+    let solved = counted.clone().filter(|entry| entry.error.is_none()).count();
+    let run = counted.map(|entry| if entry.part.is_none() { per_day } else { 1 }).sum();
+    (solved, run)
+}
+
+fn layout(entries: &[Entry], previous: Option<&[Entry]>) -> Layout {
+    let mut header = vec!["Day", "Title", "Part", "Answer", "Time"];
+    if previous.is_some() {
+        header.extend(["Previous answer", "Previous time"]);
+    }
+
+    let mut rows = Vec::new();
+    let mut screens = Vec::new();
+    for entry in entries {
+        let part = entry.part.map_or_else(|| "parse".to_string(), |part| part.to_string());
+        let outcome = entry.outcome();
+        let screen = outcome.contains('\n');
+        if screen {
+            screens.push((format!("Day {} part {}", entry.day, part), outcome.clone()));
+        }
+
+        let mut row = vec![entry.day.to_string(), entry.title.clone(), part, if screen { "screen below".to_string() } else { outcome.clone() }, entry.time()];
+        if let Some(previous) = previous {
+            let old = previous.iter().find(|old| old.day == entry.day && old.part == entry.part);
+            row.extend(match old {
+                None => ["new".to_string(), String::new()],
+                Some(old) if old.outcome() == outcome => ["same".to_string(), old.time()],
+                Some(old) if screen || old.outcome().contains('\n') => ["other screen".to_string(), old.time()],
+                Some(old) => [old.outcome(), old.time()],
+            });
+        }
+        rows.push(row);
+    }
+
+    let (days, time) = total(entries);
+    let (solved, run) = solved(entries);
+    let mut total = format!("{} days, {} of {} parts solved in {}", days, solved, run, format_duration(time));
+    if let Some(previous) = previous {
+        write!(total, ", previously {}", format_duration(self::total(previous).1)).unwrap();
+    }
+
+    Layout { header, rows, total, screens }
+}
+
+/// The entries as JSON that cannot close the comment or script element it is embedded in.
+fn data(entries: &[Entry]) -> String {
+    serde_json::to_string(entries).unwrap()
+        .replace('<', "\\u003c")
+        .replace('>', "\\u003e")
+}
+
+fn markdown(layout: &Layout, entries: &[Entry]) -> String {
+    let cell = |text: &str| text.replace('|', "\\|");
+
+    let mut report = String::new();
+    writeln!(report, "# {}", TITLE).unwrap();
+    writeln!(report).unwrap();
+    writeln!(report, "| {} |", layout.header.join(" | ")).unwrap();
+    let rule: Vec<&str> = layout.header.iter().map(|column| if matches!(*column, "Day" | "Part" | "Time" | "Previous time") { "---:" } else { "---" }).collect();
+    writeln!(report, "| {} |", rule.join(" | ")).unwrap();
+    for row in &layout.rows {
+        let cells: Vec<String> = row.iter().map(|text| cell(text)).collect();
+        writeln!(report, "| {} |", cells.join(" | ")).unwrap();
+    }
+    writeln!(report).unwrap();
+    writeln!(report, "**Total:** {}", layout.total).unwrap();
+
+    for (heading, screen) in &layout.screens {
+        writeln!(report).unwrap();
+        writeln!(report, "## {}", heading).unwrap();
+        writeln!(report).unwrap();
+        writeln!(report, "```text\n{}\n```", screen.trim_end()).unwrap();
+    }
+
+    writeln!(report).unwrap();
+    writeln!(report, "{}{}{}", MARKDOWN_DATA.0, data(entries), MARKDOWN_DATA.1).unwrap();
+    report
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn html(layout: &Layout, entries: &[Entry]) -> String {
+    let mut report = String::new();
+    writeln!(report, "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">").unwrap();
+    writeln!(report, "<title>{}</title>", TITLE).unwrap();
+    writeln!(report, "<style>\n\
+        body {{ font-family: sans-serif; margin: 2em; }}\n\
+        table {{ border-collapse: collapse; }}\n\
+        th, td {{ border: 1px solid #ccc; padding: 0.25em 0.75em; text-align: left; }}\n\
+        pre {{ font-family: monospace; line-height: 1.1; }}\n\
+        </style>\n</head>\n<body>").unwrap();
+    writeln!(report, "<h1>{}</h1>", TITLE).unwrap();
+
+    writeln!(report, "<table>").unwrap();
+    let header: Vec<String> = layout.header.iter().map(|column| format!("<th>{}</th>", column)).collect();
+    writeln!(report, "<thead><tr>{}</tr></thead>", header.concat()).unwrap();
+    writeln!(report, "<tbody>").unwrap();
+    for row in &layout.rows {
+        let cells: Vec<String> = row.iter().map(|text| format!("<td>{}</td>", escape(text))).collect();
+        writeln!(report, "<tr>{}</tr>", cells.concat()).unwrap();
+    }
+    writeln!(report, "</tbody>\n</table>").unwrap();
+    writeln!(report, "<p><strong>Total:</strong> {}</p>", escape(&layout.total)).unwrap();
+
+    for (heading, screen) in &layout.screens {
+        writeln!(report, "<h2>{}</h2>", escape(heading)).unwrap();
+        writeln!(report, "<pre>{}</pre>", escape(screen.trim_end())).unwrap();
+    }
+
+    writeln!(report, "{}{}{}", HTML_DATA.0, data(entries), HTML_DATA.1).unwrap();
+    writeln!(report, "</body>\n</html>").unwrap();
+    report
+}
+
+/// The report of the entries, with a column comparing them with the `previous` entries if given.
+pub fn render(format: Format, entries: &[Entry], previous: Option<&[Entry]>) -> String {
+    let layout = layout(entries, previous);
+    match format {
+        Format::Markdown => markdown(&layout, entries),
+        Format::Html => html(&layout, entries),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+    use rstest::rstest;
+    use crate::error::Error;
+    use crate::report::{Entry, entries, Format, parse, render};
+    use crate::registry::DAYS;
+    use crate::runner::DayResult;
+
+    fn entry(day: u8, part: u8, answer: Result<&str, &str>, solve_ns: u128) -> Entry {
+        Entry {
+            day,
+            title: DAYS[day as usize - 1].title.to_string(),
+            part: Some(part),
+            answer: answer.ok().map(String::from),
+            error: answer.err().map(String::from),
+            parse_ns: 1_000,
+            solve_ns: Some(solve_ns),
+            skipped: false,
+        }
+    }
+
+    fn run() -> Vec<Entry> {
+        vec![
+            entry(1, 1, Ok("146"), 20_000),
+            entry(1, 2, Err("not found: no location is visited twice"), 30_000),
+            entry(8, 2, Ok("#..#\n.##.\n"), 2_000_000),
+        ]
+    }
+
+    #[rstest]
+    #[case("markdown", Format::Markdown)]
+    #[case("md", Format::Markdown)]
+    #[case("html", Format::Html)]
+    fn test_format(#[case] input: &str, #[case] expected: Format) {
+        assert_eq!(input.parse::<Format>().unwrap(), expected);
+        assert!("pdf".parse::<Format>().is_err());
+    }
+
+    #[rstest]
+    #[case("report.html", Format::Html)]
+    #[case("report.md", Format::Markdown)]
+    #[case("report", Format::Markdown)]
+    fn test_format_of(#[case] path: &str, #[case] expected: Format) {
+        assert_eq!(Format::of(Path::new(path)), expected);
+    }
+
+    #[rstest]
+    fn test_entries() {
+        let result = DayResult::fixture(1, 1_000, vec![(Ok(146.into()), 20_000)]);
+        assert_eq!(entries(&[result]), vec![entry(1, 1, Ok("146"), 20_000)]);
+    }
+
+    #[rstest]
+    fn test_markdown() {
+        let report = render(Format::Markdown, &run(), None);
+        assert!(report.starts_with("# Advent of Code 2016\n\n| Day | Title | Part | Answer | Time |\n| ---: | --- | ---: | --- | ---: |\n"));
+        assert!(report.contains("| 1 | No Time for a Taxicab | 1 | 146 | 20.000 µs |\n"));
+        assert!(report.contains("| 8 | Two-Factor Authentication | 2 | screen below | 2.000 ms |\n"));
+        assert!(report.contains("**Total:** 2 days, 2 of 3 parts solved in 2.052 ms\n"));
+        assert!(report.contains("## Day 8 part 2\n\n```text\n#..#\n.##.\n```\n"));
+    }
+
+    #[rstest]
+    fn test_total_without_skipped_parts() {
+        let mut entries = run();
+        entries.push(Entry { part: None, solve_ns: None, ..entry(2, 1, Err("parse error at line 1, column 1: x"), 0) });
+        entries.push(Entry { skipped: true, ..entry(3, 1, Err("not yet implemented"), 0) });
+        entries.push(Entry { skipped: true, ..entry(3, 2, Err("not yet implemented"), 0) });
+
+        let report = render(Format::Markdown, &entries, None);
+        assert!(report.contains("**Total:** 4 days, 2 of 5 parts solved in "));
+        assert!(report.contains("| 3 | Squares With Three Sides | 1 | skipped |"));
+    }
+
+    #[rstest]
+    fn test_html() {
+        let report = render(Format::Html, &run(), None);
+        assert!(report.starts_with("<!DOCTYPE html>"));
+        assert!(report.contains("<tr><td>1</td><td>No Time for a Taxicab</td><td>1</td><td>146</td><td>20.000 µs</td></tr>"));
+        assert!(report.contains("<pre>#..#\n.##.</pre>"));
+        assert!(report.trim_end().ends_with("</html>"));
+    }
+
+    #[rstest]
+    fn test_compare() {
+        let previous = vec![entry(1, 1, Ok("145"), 10_000), entry(8, 2, Ok("#..#\n.##.\n"), 1_000_000)];
+        let report = render(Format::Markdown, &run(), Some(&previous));
+        assert!(report.contains("| Day | Title | Part | Answer | Time | Previous answer | Previous time |\n"));
+        assert!(report.contains("| 1 | No Time for a Taxicab | 1 | 146 | 20.000 µs | 145 | 10.000 µs |\n"));
+        assert!(report.contains("| 1 | No Time for a Taxicab | 2 | error: not found: no location is visited twice | 30.000 µs | new |  |\n"));
+        assert!(report.contains("| 8 | Two-Factor Authentication | 2 | screen below | 2.000 ms | same | 1.000 ms |\n"));
+        assert!(report.contains("in 2.052 ms, previously 1.012 ms\n"));
+    }
+
+    #[rstest]
+    #[case(Format::Markdown)]
+    #[case(Format::Html)]
+    fn test_read_back(#[case] format: Format) {
+        let mut entries = run();
+        entries[0].answer = Some("a|b <!-- --> </script> & \"c\"".to_string());
+        assert_eq!(parse(&render(format, &entries, None)).unwrap(), entries);
+    }
+
+    #[rstest]
+    fn test_parse_not_a_report() {
+        assert!(matches!(parse("# notes\n"), Err(Error::NotFound(_))));
+        assert!(matches!(parse("<!-- aoc-report [{]} -->"), Err(Error::Parse { .. })));
+    }
+}
@@ -0,0 +1,248 @@
+//! Runs registered days and collects their answers and timings.
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+use tracing::{debug, info, info_span, warn};
+use crate::cancel::Cancel;
+use crate::config::Params;
+use crate::error::{Error, Result};
+use crate::ioc::{InputSource, Normalize};
+use crate::registry::Day;
+use crate::solution::Answer;
+use crate::timing::{measure, Stats};
+
+/// How the selected days are run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunOptions {
+    /// Only run this part, or both parts when `None`.
+    pub part: Option<u8>,
+    /// Number of measured runs of parsing and of each part.
+    pub runs: u32,
+    /// Number of unmeasured runs before the measured runs.
+    pub warmup: u32,
+    /// Skip normalising the input text.
+    pub raw: bool,
+    /// Number of days run at the same time.
+    pub jobs: usize,
+    /// Time each run of a part may take before it is cancelled.
+    pub budget: Option<Duration>,
+}
+
+impl RunOptions {
+    /// Whether `part` is selected.
+    pub fn runs_part(&self, part: u8) -> bool {
+        self.part.is_none_or(|p| p == part)
+    }
+
+    /// A token for one run of a part, cancelled when the budget is spent.
+    pub fn cancel(&self) -> Cancel {
+        self.budget.map_or_else(Cancel::none, Cancel::after)
+    }
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        RunOptions { part: None, runs: 1, warmup: 0, raw: false, jobs: 1, budget: None }
+    }
+}
+
+/// A day selected to run, with where to read its input and the parameters to parse it with.
+pub struct Job {
+    /// The day to run.
+    pub day: &'static Day,
+    /// Where its input is read from.
+    pub source: InputSource,
+    /// The parameters it is parsed with.
+    pub params: Params,
+}
+
+impl Job {
+    /// The day with its registered input and without parameters.
+    pub fn new(day: &'static Day) -> Job {
+        Job { day, source: InputSource::File(PathBuf::from(day.input)), params: Params::default() }
+    }
+}
+
+/// The answer of a part and how long it took.
+pub struct PartResult {
+    /// The part, 1 or 2.
+    pub part: u8,
+    /// The answer of the last run, or why it failed.
+    pub answer: Result<Answer>,
+    /// The timings of the measured runs.
+    pub timing: Stats,
+}
+
+impl PartResult {
+    /// Whether the part is skipped because it is not implemented yet.
+    pub fn skipped(&self) -> bool {
+        matches!(self.answer, Err(Error::NotImplemented))
+    }
+}
+
+/// The outcome of running a day.
+pub struct DayResult {
+    /// The day that was run.
+    pub day: &'static Day,
+    /// The timings of parsing.
+    pub parse: Stats,
+    /// Why parsing failed; the parts are not run then.
+    pub parse_error: Option<Error>,
+    /// The selected parts.
+    pub parts: Vec<PartResult>,
+}
+
+impl DayResult {
+    /// Whether the day parsed and every part has an answer or is skipped.
+    pub fn success(&self) -> bool {
+        self.parse_error.is_none() && self.parts.iter().all(|part| part.answer.is_ok() || part.skipped())
+    }
+
+    /// A result of day `number` as if run once, parsing in `parse_ns` and solving the parts, in
+    /// order from part 1, with the given answers and times in nanoseconds.
+    #[cfg(test)]
+    pub(crate) fn fixture(number: u8, parse_ns: u64, parts: Vec<(Result<Answer>, u64)>) -> DayResult {
+        let stats = |nanos| Stats { runs: 1, median: Duration::from_nanos(nanos), ..Stats::default() };
+        let day = crate::registry::DAYS.iter().find(|day| day.number == number).unwrap();
+        let parts = parts.into_iter().zip(1..)
+            .map(|((answer, nanos), part)| PartResult { part, answer, timing: stats(nanos) })
+            .collect();
+        DayResult { day, parse: stats(parse_ns), parse_error: None, parts }
+    }
+}
+
+/// Reads the input of a day, normalised the way the day expects unless `raw` is set.
+pub fn load(day: &Day, source: &InputSource, raw: bool) -> Result<String> {
+    let normalize = if raw { Normalize::NONE } else { day.puzzle.normalize() };
+    Ok(source.load(&normalize)?)
+}
+
+/// Parses the text and runs the selected parts of the day, on the calling thread.
+pub fn run_day(day: &'static Day, text: &str, params: &Params, options: &RunOptions) -> DayResult {
+    let (warmup, runs) = (options.warmup as usize, options.runs as usize);
+    let _day = info_span!("day", number = day.number).entered();
+
+    let (input, parse) = measure(warmup, runs, || day.puzzle.parse(text, params), |input| input.is_err());
+    let input = match input {
+        Ok(input) => input,
+        Err(e) => {
+            warn!(error = %e, "parse failed");
+            return DayResult { day, parse, parse_error: Some(e), parts: Vec::new() };
+        },
+    };
+    debug!(median = ?parse.median, "parsed");
+
+    let mut parts = Vec::new();
+    for part in [1, 2] {
+        if !options.runs_part(part) {
+            continue;
+        }
+
+        let _part = info_span!("part", part).entered();
+        let (answer, timing) = measure(warmup, runs, || {
+            let cancel = options.cancel();
+            if part == 1 { day.puzzle.part1(input.as_ref(), &cancel) } else { day.puzzle.part2(input.as_ref(), &cancel) }
+        }, |answer| answer.is_err());
+        match &answer {
+            Ok(answer) => info!(%answer, median = ?timing.median, "solved"),
+            Err(Error::NotImplemented) => info!("skipped, not yet implemented"),
+            Err(e) => warn!(error = %e, "failed"),
+        }
+        parts.push(PartResult { part, answer, timing });
+    }
+
+    DayResult { day, parse, parse_error: None, parts }
+}
+
+/// Loads the input of every day before running them, so a missing input is reported before
+/// any time is spent solving. Days are run on `jobs` threads; every day is still timed on its
+/// own thread, and `report` sees the results in the order the days were given.
+pub fn run(jobs: &[Job], options: &RunOptions, mut report: impl FnMut(&DayResult)) -> Result<Vec<DayResult>> {
+    let mut inputs = Vec::new();
+    for job in jobs {
+        let text = load(job.day, &job.source, options.raw)?;
+        debug!(day = job.day.number, source = %job.source, bytes = text.len(), "loaded");
+        inputs.push((job, text));
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut results: Vec<Option<DayResult>> = inputs.iter().map(|_| None).collect();
+    let (sender, receiver) = mpsc::channel();
+
+    thread::scope(|scope| {
+        for _ in 0..options.jobs.clamp(1, inputs.len().max(1)) {
+            let (sender, next, inputs) = (sender.clone(), &next, &inputs);
+            scope.spawn(move || loop {
+                let idx = next.fetch_add(1, Ordering::Relaxed);
+                let Some((job, text)) = inputs.get(idx) else { break };
+                if sender.send((idx, run_day(job.day, text, &job.params, options))).is_err() {
+                    break;
+                }
+            });
+        }
+        drop(sender);
+
+        let mut reported = 0;
+        for (idx, result) in receiver {
+            results[idx] = Some(result);
+            while let Some(Some(result)) = results.get(reported) {
+                report(result);
+                reported += 1;
+            }
+        }
+    });
+
+    Ok(results.into_iter().flatten().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+    use rstest::rstest;
+    use toml::Value;
+    use crate::error::Error;
+    use crate::registry::DAYS;
+    use crate::runner::{Job, run, RunOptions};
+
+    #[rstest]
+    #[case(1)]
+    #[case(3)]
+    #[case(16)]
+    fn test_run_in_order(#[case] jobs: usize) {
+        let days: Vec<Job> = [1, 2, 3, 4].iter()
+            .map(|number| Job::new(DAYS.iter().find(|day| day.number == *number).unwrap()))
+            .collect();
+
+        let mut reported = Vec::new();
+        let results = run(&days, &RunOptions { jobs, ..RunOptions::default() }, |result| reported.push(result.day.number)).unwrap();
+
+        let expected: Vec<u8> = days.iter().map(|job| job.day.number).collect();
+        assert_eq!(reported, expected);
+        assert_eq!(results.iter().map(|result| result.day.number).collect::<Vec<u8>>(), expected);
+        assert!(results.iter().all(|result| result.success()));
+        assert_eq!(*results[0].parts[0].answer.as_ref().unwrap(), "146");
+    }
+
+    #[rstest]
+    fn test_run_with_params() {
+        let mut job = Job::new(&DAYS[9]);
+        job.params.set("outputs", Value::Array(vec![Value::Integer(0)]));
+        let results = run(&[job], &RunOptions::default(), |_| {}).unwrap();
+        assert_eq!(*results[0].parts[1].answer.as_ref().unwrap(), "59");
+
+        let mut job = Job::new(&DAYS[0]);
+        job.params.set("width", Value::Integer(3));
+        let results = run(&[job], &RunOptions::default(), |_| {}).unwrap();
+        assert!(results[0].parse_error.is_some());
+    }
+
+    #[rstest]
+    fn test_run_out_of_budget() {
+        let options = RunOptions { part: Some(1), budget: Some(Duration::from_millis(50)), ..RunOptions::default() };
+        let results = run(&[Job::new(&DAYS[4])], &options, |_| {}).unwrap();
+        assert!(matches!(&results[0].parts[0].answer, Err(Error::TimedOut { partial: Some(p) }) if p.len() == 8));
+    }
+}
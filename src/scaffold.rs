@@ -0,0 +1,173 @@
+//! Generates the module of a new day from the `day.rs` template and registers it.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use crate::error::{Error, Result};
+
+const TEMPLATE: &str = include_str!("day.rs");
+
+/// A line declaring a day module, `pub mod dayN;`.
+const MODULE: (&str, &str) = ("pub mod day", ";");
+/// A line of `DAYS`, `    Day { number: N, ...`.
+const ENTRY: (&str, &str) = ("    Day { number: ", ",");
+const IMPORTS: &str = "use crate::{";
+
+/// The template with its placeholders filled in for `day`.
+pub fn render(day: u8, title: &str) -> String {
+    let heading = if title.ends_with(['.', '?', '!']) { title.to_string() } else { format!("{}.", title) };
+    TEMPLATE
+        .replace("Day N: template for a new day.", &format!("Day {}: {}", day, heading))
+        .replace("day N", &format!("day {}", day))
+        .replace("dayN", &format!("day{}", day))
+        .replace("DayN", &format!("Day{}", day))
+}
+
+/// The number and start of every line made up of `prefix`, a number and then `suffix`.
+fn numbers((prefix, suffix): (&str, &str), text: &str) -> Vec<(u8, usize)> {
+    let mut start = 0;
+    let mut numbers = Vec::new();
+    for line in text.split_inclusive('\n') {
+        let rest = line.strip_prefix(prefix).unwrap_or_default();
+        let digits = rest.find(|ch: char| !ch.is_ascii_digit()).unwrap_or(rest.len());
+        if digits > 0 && rest[digits..].trim_end_matches('\n').starts_with(suffix) {
+            if let Ok(number) = rest[..digits].parse() {
+                numbers.push((number, start));
+            }
+        }
+        start += line.len();
+    }
+
+    numbers
+}
+
+/// Adds `pub mod dayN;` to the crate root, after the days with a lower number.
+pub fn register_module(lib: &str, day: u8) -> Result<String> {
+    let modules = numbers(MODULE, lib);
+    if modules.iter().any(|(number, _)| *number == day) {
+        return Err(Error::InvalidState(format!("module day{} is already declared", day)));
+    }
+
+    let offset = match modules.iter().find(|(number, _)| *number > day) {
+        Some((_, start)) => *start,
+        None => {
+            let (_, start) = modules.last()
+                .ok_or_else(|| Error::NotFound("no day modules to add to".to_string()))?;
+            start + lib[*start..].find('\n').map_or(lib.len() - start, |idx| idx + 1)
+        },
+    };
+
+    let mut lib = lib.to_string();
+    lib.insert_str(offset, &format!("pub mod day{};\n", day));
+    Ok(lib)
+}
+
+/// Adds the day to the imports and to `DAYS` in the registry, keeping both in order.
+pub fn register_day(registry: &str, day: u8, title: &str) -> Result<String> {
+    let entries = numbers(ENTRY, registry);
+    if entries.iter().any(|(number, _)| *number == day) {
+        return Err(Error::InvalidState(format!("day {} is already registered", day)));
+    }
+
+    let entry = format!("    Day {{ number: {}, title: {:?}, input: \"./res/input-day{}.txt\", puzzle: &day{}::Day{} }},\n",
+                        day, title, day, day, day);
+    let offset = match entries.iter().find(|(number, _)| *number > day) {
+        Some((_, start)) => *start,
+        None => registry.rfind("];").ok_or_else(|| Error::NotFound("the end of DAYS".to_string()))?,
+    };
+    let mut registry = registry.to_string();
+    registry.insert_str(offset, &entry);
+
+    let imports = registry.find(IMPORTS)
+        .map(|idx| idx + IMPORTS.len())
+        .and_then(|start| Some(start..start + registry[start..].find("};")?))
+        .ok_or_else(|| Error::NotFound("the day imports of the registry".to_string()))?;
+    let mut modules: Vec<String> = registry[imports.clone()].split(',').map(|module| module.trim().to_string()).collect();
+    modules.push(format!("day{}", day));
+    modules.sort();
+    registry.replace_range(imports, &modules.join(", "));
+
+    Ok(registry)
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).map_err(|e| Error::InvalidState(format!("unable to read {}: {}", path.display(), e)))
+}
+
+fn write(path: &Path, text: &str) -> Result<()> {
+    fs::write(path, text).map_err(|e| Error::InvalidState(format!("unable to write {}: {}", path.display(), e)))
+}
+
+/// Creates `src/dayN.rs` and a placeholder input below `root` and registers the day,
+/// returning the files that were written.
+pub fn new_day(root: &Path, day: u8, title: &str) -> Result<Vec<PathBuf>> {
+    let module = root.join(format!("src/day{}.rs", day));
+    if module.exists() {
+        return Err(Error::InvalidState(format!("{} already exists", module.display())));
+    }
+
+    let lib_path = root.join("src/lib.rs");
+    let registry_path = root.join("src/registry.rs");
+    let lib = register_module(&read(&lib_path)?, day)?;
+    let registry = register_day(&read(&registry_path)?, day, title)?;
+
+    write(&module, &render(day, title))?;
+    write(&lib_path, &lib)?;
+    write(&registry_path, &registry)?;
+    let mut written = vec![module, lib_path, registry_path];
+
+    let input = root.join(format!("res/input-day{}.txt", day));
+    if !input.exists() {
+        write(&input, "")?;
+        written.push(input);
+    }
+
+    Ok(written)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use crate::error::Error;
+    use crate::scaffold::{register_day, register_module, render};
+
+    const REGISTRY: &str = "use crate::{day1, day10, day2};\n\npub static DAYS: &[Day] = &[\n    \
+        Day { number: 1, title: \"A\", input: \"./res/input-day1.txt\", puzzle: &day1::Day1 },\n    \
+        Day { number: 2, title: \"B\", input: \"./res/input-day2.txt\", puzzle: &day2::Day2 },\n    \
+        Day { number: 10, title: \"C\", input: \"./res/input-day10.txt\", puzzle: &day10::Day10 },\n];\n";
+
+    #[rstest]
+    fn test_render() {
+        let module = render(11, "Radioisotope Thermoelectric Generators");
+        assert!(module.starts_with("//! Day 11: Radioisotope Thermoelectric Generators.\n"));
+        assert!(module.contains("/// The [`Solution`] of day 11.\npub struct Day11;"));
+        assert!(module.contains("use crate::day11::{parse, part1, part2};"));
+        assert!(!module.contains("dayN") && !module.contains("DayN"));
+        assert!(module.contains("Err(Error::NotImplemented)") && module.contains("#[ignore = \"not yet implemented\"]"));
+        assert!(render(5, "Chess?").starts_with("//! Day 5: Chess?\n"));
+    }
+
+    #[rstest]
+    #[case(11, "pub mod day9;\npub mod day10;\npub mod day11;\npub mod error;\n")]
+    #[case(3, "pub mod day3;\npub mod day9;\npub mod day10;\npub mod error;\n")]
+    fn test_register_module(#[case] day: u8, #[case] expected: &str) {
+        assert_eq!(register_module("pub mod day9;\npub mod day10;\npub mod error;\n", day).unwrap(), expected);
+    }
+
+    #[rstest]
+    fn test_register_module_twice() {
+        assert!(matches!(register_module("pub mod day9;\n", 9), Err(Error::InvalidState(_))));
+    }
+
+    #[rstest]
+    fn test_register_day() {
+        let registry = register_day(REGISTRY, 3, "Title \"3\"").unwrap();
+        assert!(registry.starts_with("use crate::{day1, day10, day2, day3};"));
+        assert!(registry.contains("day2::Day2 },\n    \
+            Day { number: 3, title: \"Title \\\"3\\\"\", input: \"./res/input-day3.txt\", puzzle: &day3::Day3 },\n    \
+            Day { number: 10,"));
+
+        let registry = register_day(REGISTRY, 11, "D").unwrap();
+        assert!(registry.ends_with("puzzle: &day11::Day11 },\n];\n"));
+        assert!(matches!(register_day(REGISTRY, 2, "B"), Err(Error::InvalidState(_))));
+    }
+}
@@ -0,0 +1,80 @@
+//! The traits every day implements.
+
+use std::any::Any;
+use crate::cancel::Cancel;
+use crate::config::Params;
+use crate::error::{Error, Result};
+use crate::ioc::Normalize;
+
+pub use crate::answer::Answer;
+
+/// A puzzle solution: the input is parsed once and shared by both parts.
+pub trait Solution {
+    /// The model the input is parsed into.
+    type Input;
+
+    /// How the input text is cleaned up before parsing.
+    fn normalize(&self) -> Normalize {
+        Normalize::default()
+    }
+
+    /// Parses the input with the default parameters.
+    fn parse(&self, input: &str) -> Result<Self::Input>;
+
+    /// Parses the input with the parameters configured for the day; days without parameters reject any.
+    fn parse_with(&self, input: &str, params: &Params) -> Result<Self::Input> {
+        params.none()?;
+        self.parse(input)
+    }
+
+    /// Solves part 1.
+    fn part1(&self, input: &Self::Input) -> Result<Answer>;
+
+    /// Solves part 2.
+    fn part2(&self, input: &Self::Input) -> Result<Answer>;
+
+    /// Solves part 1, giving up once `cancel` is cancelled; quick days never check it.
+    fn part1_with(&self, input: &Self::Input, _cancel: &Cancel) -> Result<Answer> {
+        self.part1(input)
+    }
+
+    /// Solves part 2, giving up once `cancel` is cancelled; quick days never check it.
+    fn part2_with(&self, input: &Self::Input, _cancel: &Cancel) -> Result<Answer> {
+        self.part2(input)
+    }
+}
+
+/// Type erased version of a Solution, so days with different input types can share the registry.
+pub trait Puzzle: Sync {
+    /// See [`Solution::normalize`].
+    fn normalize(&self) -> Normalize;
+    /// See [`Solution::parse_with`].
+    fn parse(&self, input: &str, params: &Params) -> Result<Box<dyn Any>>;
+    /// See [`Solution::part1_with`]; `input` must come from [`Puzzle::parse`].
+    fn part1(&self, input: &dyn Any, cancel: &Cancel) -> Result<Answer>;
+    /// See [`Solution::part2_with`]; `input` must come from [`Puzzle::parse`].
+    fn part2(&self, input: &dyn Any, cancel: &Cancel) -> Result<Answer>;
+}
+
+impl<S> Puzzle for S where S: Solution + Sync, S::Input: 'static {
+    fn normalize(&self) -> Normalize {
+        Solution::normalize(self)
+    }
+
+    fn parse(&self, input: &str, params: &Params) -> Result<Box<dyn Any>> {
+        Ok(Box::new(Solution::parse_with(self, input, params)?))
+    }
+
+    fn part1(&self, input: &dyn Any, cancel: &Cancel) -> Result<Answer> {
+        Solution::part1_with(self, downcast::<S>(input)?, cancel)
+    }
+
+    fn part2(&self, input: &dyn Any, cancel: &Cancel) -> Result<Answer> {
+        Solution::part2_with(self, downcast::<S>(input)?, cancel)
+    }
+}
+
+fn downcast<S>(input: &dyn Any) -> Result<&S::Input> where S: Solution, S::Input: 'static {
+    input.downcast_ref::<S::Input>()
+        .ok_or_else(|| Error::InvalidState("input was not parsed by this puzzle".to_string()))
+}
@@ -0,0 +1,161 @@
+//! Monotonic timing of repeated runs.
+
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+use crate::runner::DayResult;
+
+/// Summary of repeated measurements of the same piece of work.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Stats {
+    /// The number of measurements.
+    pub runs: usize,
+    /// The fastest measurement.
+    pub min: Duration,
+    /// The median measurement.
+    pub median: Duration,
+    /// The mean of the measurements.
+    pub mean: Duration,
+    /// The standard deviation of the measurements.
+    pub stddev: Duration,
+}
+
+impl Stats {
+    /// The summary of the samples; all zero without samples.
+    pub fn from_samples(samples: &[Duration]) -> Stats {
+        if samples.is_empty() {
+            return Stats::default();
+        }
+
+        let mut sorted = samples.to_vec();
+        sorted.sort();
+
+        let runs = sorted.len();
+        let median = if runs % 2 == 1 {
+            sorted[runs / 2]
+        } else {
+            (sorted[runs / 2 - 1] + sorted[runs / 2]) / 2
+        };
+
+        let mean = sorted.iter().map(|d| d.as_secs_f64()).sum::<f64>() / runs as f64;
+        let variance = sorted.iter()
+            .map(|d| (d.as_secs_f64() - mean).powi(2))
+            .sum::<f64>() / runs as f64;
+
+        Stats {
+            runs,
+            min: sorted[0],
+            median,
+            mean: Duration::from_secs_f64(mean),
+            stddev: Duration::from_secs_f64(variance.sqrt()),
+        }
+    }
+}
+
+/// Runs `work` `warmup` times without measuring, then `runs` times measured. The value of the
+/// last run is returned; measuring stops early when `done` says the value should not be repeated.
+pub fn measure<T>(warmup: usize, runs: usize, mut work: impl FnMut() -> T, done: impl Fn(&T) -> bool) -> (T, Stats) {
+    for _ in 0..warmup {
+        if done(&work()) {
+            break;
+        }
+    }
+
+    let mut samples = Vec::with_capacity(runs.max(1));
+    loop {
+        let start = Instant::now();
+        let value = work();
+        samples.push(start.elapsed());
+
+        if samples.len() >= runs || done(&value) {
+            return (value, Stats::from_samples(&samples));
+        }
+    }
+}
+
+/// The duration in the largest unit below it: ns, µs, ms or s.
+pub fn format_duration(duration: Duration) -> String {
+    let nanos = duration.as_nanos();
+    if nanos < 1_000 {
+        format!("{} ns", nanos)
+    } else if nanos < 1_000_000 {
+        format!("{:.3} µs", nanos as f64 / 1e3)
+    } else if nanos < 1_000_000_000 {
+        format!("{:.3} ms", nanos as f64 / 1e6)
+    } else {
+        format!("{:.3} s", nanos as f64 / 1e9)
+    }
+}
+
+/// Renders the timing statistics of parsing and each part of the results as a table.
+pub fn table(results: &[DayResult]) -> String {
+    let mut table = String::new();
+    writeln!(table, "{:>3}  {:<6} {:>5}  {:>12}  {:>12}  {:>12}  {:>12}", "day", "stage", "runs", "min", "median", "mean", "stddev").unwrap();
+
+    for result in results {
+        let stages = std::iter::once(("parse".to_string(), &result.parse))
+            .chain(result.parts.iter().map(|part| (format!("part {}", part.part), &part.timing)));
+
+        for (stage, stats) in stages {
+            writeln!(table, "{:>3}  {:<6} {:>5}  {:>12}  {:>12}  {:>12}  {:>12}",
+                     result.day.number, stage, stats.runs,
+                     format_duration(stats.min), format_duration(stats.median),
+                     format_duration(stats.mean), format_duration(stats.stddev)).unwrap();
+        }
+    }
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::time::Duration;
+    use rstest::rstest;
+    use crate::timing::{format_duration, measure, Stats};
+
+    fn ms(values: &[u64]) -> Vec<Duration> {
+        values.iter().map(|v| Duration::from_millis(*v)).collect()
+    }
+
+    #[rstest]
+    fn test_stats_odd() {
+        let stats = Stats::from_samples(&ms(&[4, 2, 9]));
+        assert_eq!(stats.runs, 3);
+        assert_eq!(stats.min, Duration::from_millis(2));
+        assert_eq!(stats.median, Duration::from_millis(4));
+        assert_eq!(stats.mean, Duration::from_millis(5));
+    }
+
+    #[rstest]
+    fn test_stats_even() {
+        let stats = Stats::from_samples(&ms(&[2, 4, 4, 4, 5, 5, 7, 9]));
+        assert_eq!(stats.median, Duration::from_millis(4) + Duration::from_micros(500));
+        assert_eq!(stats.mean, Duration::from_millis(5));
+        assert_eq!(stats.stddev, Duration::from_millis(2));
+    }
+
+    #[rstest]
+    fn test_stats_empty() {
+        assert_eq!(Stats::from_samples(&[]), Stats::default());
+    }
+
+    #[rstest]
+    #[case(2, 5, false, 7, 5)]
+    #[case(2, 5, true, 2, 1)]
+    #[case(0, 0, false, 1, 1)]
+    fn test_measure(#[case] warmup: usize, #[case] runs: usize, #[case] done: bool, #[case] calls: usize, #[case] measured: usize) {
+        let count = Cell::new(0);
+        let (value, stats) = measure(warmup, runs, || { count.set(count.get() + 1); count.get() }, |_| done);
+        assert_eq!(count.get(), calls);
+        assert_eq!(value, calls);
+        assert_eq!(stats.runs, measured);
+    }
+
+    #[rstest]
+    #[case(Duration::from_nanos(999), "999 ns")]
+    #[case(Duration::from_micros(1500), "1.500 ms")]
+    #[case(Duration::from_millis(2250), "2.250 s")]
+    fn test_format_duration(#[case] duration: Duration, #[case] expected: &str) {
+        assert_eq!(format_duration(duration), expected);
+    }
+}
@@ -0,0 +1,189 @@
+//! Compares the answers of a run with the expected answers.
+
+use std::fmt;
+use std::fmt::Write;
+use crate::answer::Answer;
+use crate::answers::Answers;
+use crate::error::Error;
+use crate::runner::DayResult;
+
+/// The outcome of checking a part.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Status {
+    /// The answer is the expected one.
+    Pass,
+    /// The answer differs from the expected one.
+    Fail,
+    /// Parsing or the part failed.
+    Error,
+    /// There is no expected answer to compare with.
+    Missing,
+    /// The part is not implemented yet.
+    Skipped,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            Status::Pass => "pass",
+            Status::Fail => "FAIL",
+            Status::Error => "ERROR",
+            Status::Missing => "missing",
+            Status::Skipped => "skipped",
+        };
+        f.pad(text)
+    }
+}
+
+/// The check of one part of a day, or of the parsing of a day as part 0.
+#[derive(Debug)]
+pub struct Check {
+    /// The number of the day.
+    pub day: u8,
+    /// The part, 0 when parsing failed.
+    pub part: u8,
+    /// The outcome.
+    pub status: Status,
+    /// The expected answer, if known.
+    pub expected: Option<Answer>,
+    /// The answer found, or the error.
+    pub actual: String,
+}
+
+impl Check {
+    /// Whether the check fails the verification: a wrong answer or an error.
+    pub fn failed(&self) -> bool {
+        matches!(self.status, Status::Fail | Status::Error)
+    }
+}
+
+/// Checks every part of the results against the answers file.
+pub fn verify(results: &[DayResult], answers: &Answers) -> Vec<Check> {
+    results.iter()
+        .flat_map(|result| check(result, |part| answers.expected(result.day.number, part)))
+        .collect()
+}
+
+/// Checks the answers of a single run against the expected answer of each part.
+pub fn check(result: &DayResult, expected: impl Fn(u8) -> Option<Answer>) -> Vec<Check> {
+    let number = result.day.number;
+    if let Some(e) = &result.parse_error {
+        return vec![Check { day: number, part: 0, status: Status::Error, expected: None, actual: e.to_string() }];
+    }
+
+    let mut checks = Vec::new();
+    for part in &result.parts {
+        let expected = expected(part.part);
+        let (status, actual) = match (&part.answer, &expected) {
+            (Err(e @ Error::NotImplemented), _) => (Status::Skipped, e.to_string()),
+            (Err(e), _) => (Status::Error, e.to_string()),
+            (Ok(answer), None) => (Status::Missing, answer.to_string()),
+            (Ok(answer), Some(expected)) if answer == expected => (Status::Pass, answer.to_string()),
+            (Ok(answer), Some(_)) => (Status::Fail, answer.to_string()),
+        };
+        checks.push(Check { day: number, part: part.part, status, expected, actual });
+    }
+
+    checks
+}
+
+/// The answer itself, or its line count for an answer spanning several lines.
+pub fn summary(answer: &str) -> String {
+    let lines = answer.lines().count();
+    if lines > 1 {
+        format!("<{} lines>", lines)
+    } else {
+        answer.to_string()
+    }
+}
+
+/// The lines of both answers, marking lines only expected with `-` and lines only found with `+`.
+pub fn diff(expected: &str, actual: &str) -> Vec<String> {
+    let expected: Vec<&str> = expected.lines().collect();
+    let actual: Vec<&str> = actual.lines().collect();
+
+    let mut lines = Vec::new();
+    for idx in 0..expected.len().max(actual.len()) {
+        match (expected.get(idx), actual.get(idx)) {
+            (Some(e), Some(a)) if e == a => lines.push(format!("  {}", e)),
+            (e, a) => {
+                if let Some(e) = e {
+                    lines.push(format!("- {}", e));
+                }
+                if let Some(a) = a {
+                    lines.push(format!("+ {}", a));
+                }
+            }
+        }
+    }
+
+    lines
+}
+
+/// Renders the checks as a pass/fail table, followed by a diff for every failed check.
+pub fn table(checks: &[Check]) -> String {
+    let mut table = String::new();
+    writeln!(table, "{:>3} {:>4}  {:<7}  {:<16}  actual", "day", "part", "status", "expected").unwrap();
+    for check in checks {
+        let part = if check.part == 0 { "-".to_string() } else { check.part.to_string() };
+        let expected = check.expected.as_ref().map_or("-".to_string(), |expected| summary(&expected.to_string()));
+        writeln!(table, "{:>3} {:>4}  {:<7}  {:<16}  {}", check.day, part, check.status, expected, summary(&check.actual)).unwrap();
+    }
+
+    for check in checks.iter().filter(|check| check.failed()) {
+        writeln!(table).unwrap();
+        match (&check.status, &check.expected) {
+            (Status::Fail, Some(expected)) => {
+                writeln!(table, "day {} part {}:", check.day, check.part).unwrap();
+                for line in diff(&expected.to_string(), &check.actual) {
+                    writeln!(table, "  {}", line).unwrap();
+                }
+            },
+            _ => writeln!(table, "day {} part {}: {}", check.day, check.part, check.actual).unwrap(),
+        }
+    }
+
+    let passed = checks.iter().filter(|check| check.status == Status::Pass).count();
+    let failed = checks.iter().filter(|check| check.failed()).count();
+    let skipped = checks.iter().filter(|check| check.status == Status::Skipped).count();
+    writeln!(table).unwrap();
+    writeln!(table, "{} passed, {} failed, {} skipped, {} without expected answer",
+             passed, failed, skipped, checks.len() - passed - failed - skipped).unwrap();
+
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use crate::answers::Answers;
+    use crate::error::Error;
+    use crate::runner::DayResult;
+    use crate::verify::{diff, Status, verify};
+
+    #[rstest]
+    fn test_verify() {
+        let answers = Answers::parse("[day1]\npart1 = 146\npart2 = 131\n[day2]\npart1 = '61529'").unwrap();
+        let results = vec![
+            DayResult::fixture(1, 0, vec![(Ok(146.into()), 0), (Ok(130.into()), 0)]),
+            DayResult::fixture(2, 0, vec![(Err(Error::NotFound("code".to_string())), 0), (Ok("C2C28".into()), 0)]),
+            DayResult::fixture(3, 0, vec![(Err(Error::NotImplemented), 0)]),
+        ];
+
+        let checks = verify(&results, &answers);
+        let statuses: Vec<Status> = checks.iter().map(|check| check.status).collect();
+        assert_eq!(statuses, vec![Status::Pass, Status::Fail, Status::Error, Status::Missing, Status::Skipped]);
+        assert!(!checks[4].failed());
+        assert!(results[2].success() && !results[1].success());
+    }
+
+    #[rstest]
+    fn test_diff_single_line() {
+        assert_eq!(diff("146", "147"), vec!["- 146", "+ 147"]);
+    }
+
+    #[rstest]
+    fn test_diff_multi_line() {
+        assert_eq!(diff("#.\n.#\n", "#.\n##\n#."), vec!["  #.", "- .#", "+ ##", "+ #."]);
+    }
+}
@@ -0,0 +1,153 @@
+//! Polls the inputs and examples of the selected days, so they can be run again whenever one of
+//! them changes, and reports how the answers differ from the previous run.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+use crate::examples::Example;
+use crate::ioc::InputSource;
+use crate::runner::{DayResult, Job};
+use crate::verify::{check, Check, diff, Status};
+
+/// The modification time and length of every watched file, `None` when it cannot be read.
+pub type Stamps = BTreeMap<PathBuf, Option<(SystemTime, u64)>>;
+
+/// The input files of the jobs and the example files of their days, `dir/dayN/*`.
+pub fn files(jobs: &[Job], dir: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    for job in jobs {
+        if let InputSource::File(path) = &job.source {
+            files.push(path.clone());
+        }
+
+        // A day without examples has no directory, or it was just removed.
+        let Ok(entries) = fs::read_dir(dir.join(format!("day{}", job.day.number))) else { continue };
+        let mut examples: Vec<PathBuf> = entries.flatten().map(|entry| entry.path()).filter(|path| path.is_file()).collect();
+        examples.sort();
+        files.extend(examples);
+    }
+
+    files
+}
+
+/// The current stamp of every file.
+pub fn stamps(files: &[PathBuf]) -> Stamps {
+    files.iter()
+        .map(|path| {
+            let stamp = fs::metadata(path).ok().and_then(|meta| Some((meta.modified().ok()?, meta.len())));
+            (path.clone(), stamp)
+        })
+        .collect()
+}
+
+/// The files added, removed or modified since the `previous` poll.
+pub fn changed(previous: &Stamps, current: &Stamps) -> Vec<PathBuf> {
+    let removed = previous.keys().filter(|path| !current.contains_key(*path));
+    let modified = current.iter().filter(|(path, stamp)| previous.get(*path) != Some(stamp)).map(|(path, _)| path);
+    let mut paths: Vec<PathBuf> = removed.chain(modified).cloned().collect();
+    paths.sort();
+    paths
+}
+
+fn answer(check: &Check) -> String {
+    match check.status {
+        Status::Error => format!("error: {}", check.actual),
+        _ => check.actual.clone(),
+    }
+}
+
+/// The answer, or error, of every part run, labelled like `day 8 part 2` or `day 8 simple part 1`
+/// for an example.
+pub fn answers(results: &[DayResult], examples: &[(&Example, Vec<Check>)]) -> Vec<(String, String)> {
+    let label = |day: u8, name: Option<&str>, part: u8| {
+        let name = name.map_or_else(String::new, |name| format!(" {}", name));
+        if part == 0 { format!("day {}{}", day, name) } else { format!("day {}{} part {}", day, name, part) }
+    };
+
+    let days = results.iter()
+        .flat_map(|result| check(result, |_| None))
+        .map(|check| (label(check.day, None, check.part), answer(&check)));
+    let examples = examples.iter()
+        .flat_map(|(example, checks)| checks.iter().map(move |check| (label(check.day, Some(&example.name), check.part), answer(check))));
+    days.chain(examples).collect()
+}
+
+/// The answers that are new or differ from the `previous` run; answers of several lines, like a
+/// screen, are shown as a diff.
+pub fn report(previous: &[(String, String)], current: &[(String, String)]) -> String {
+    let mut report = String::new();
+    for (label, answer) in current {
+        let old = previous.iter().find(|(old, _)| old == label).map(|(_, answer)| answer);
+        match old {
+            Some(old) if old == answer => continue,
+            Some(old) if old.contains('\n') || answer.contains('\n') => {
+                writeln!(report, "{}:", label).unwrap();
+                for line in diff(old, answer) {
+                    writeln!(report, "  {}", line).unwrap();
+                }
+            },
+            Some(old) => writeln!(report, "{}: {} -> {}", label, old, answer).unwrap(),
+            None if answer.contains('\n') => {
+                writeln!(report, "{}:", label).unwrap();
+                for line in answer.lines() {
+                    writeln!(report, "  {}", line).unwrap();
+                }
+            },
+            None => writeln!(report, "{}: {}", label, answer).unwrap(),
+        }
+    }
+
+    for (label, _) in previous.iter().filter(|(label, _)| !current.iter().any(|(new, _)| new == label)) {
+        writeln!(report, "{}: no longer run", label).unwrap();
+    }
+
+    if report.is_empty() {
+        report.push_str("answers unchanged\n");
+    }
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use rstest::rstest;
+    use crate::registry::DAYS;
+    use crate::runner::Job;
+    use crate::testing::TempDir;
+    use crate::watch::{changed, files, report, stamps};
+
+    fn answers(answers: &[(&str, &str)]) -> Vec<(String, String)> {
+        answers.iter().map(|(label, answer)| (label.to_string(), answer.to_string())).collect()
+    }
+
+    #[rstest]
+    #[case(&[], &[("day 1 part 1", "12")], "day 1 part 1: 12\n")]
+    #[case(&[("day 1 part 1", "12")], &[("day 1 part 1", "12")], "answers unchanged\n")]
+    #[case(&[("day 1 part 1", "12")], &[("day 1 part 1", "13")], "day 1 part 1: 12 -> 13\n")]
+    #[case(&[("day 1 part 1", "12")], &[("day 1 part 1", "error: no input")], "day 1 part 1: 12 -> error: no input\n")]
+    #[case(&[("day 8 part 2", "#.\n.#")], &[("day 8 part 2", "#.\n##")], "day 8 part 2:\n    #.\n  - .#\n  + ##\n")]
+    #[case(&[], &[("day 8 part 2", "#.\n.#")], "day 8 part 2:\n  #.\n  .#\n")]
+    #[case(&[("day 1 part 1", "12"), ("day 1 part 2", "4")], &[("day 1 part 2", "4")], "day 1 part 1: no longer run\n")]
+    fn test_report(#[case] previous: &[(&str, &str)], #[case] current: &[(&str, &str)], #[case] expected: &str) {
+        assert_eq!(report(&answers(previous), &answers(current)), expected);
+    }
+
+    #[rstest]
+    fn test_changed() {
+        let dir = TempDir::new("watch");
+        fs::create_dir_all(dir.join("day1")).unwrap();
+        fs::write(dir.join("day1").join("example.txt"), "R2").unwrap();
+
+        let jobs = [Job::new(&DAYS[0]), Job::new(&DAYS[1])];
+        let watched = files(&jobs, &dir);
+        let before = stamps(&watched);
+        fs::write(dir.join("day1").join("example.txt"), "R2, L3").unwrap();
+        let after = stamps(&files(&jobs, &dir));
+
+        assert_eq!(watched, vec![DAYS[0].input.into(), dir.join("day1").join("example.txt"), DAYS[1].input.into()]);
+        assert!(changed(&before, &before).is_empty());
+        assert_eq!(changed(&before, &after), vec![dir.join("day1").join("example.txt")]);
+    }
+}